@@ -1,23 +1,5 @@
-use arloader::transaction::{stringify, Base64};
+use arloader::transaction::{RawChunk, TransactionOffset};
 use reqwest;
-use serde::Deserialize;
-
-#[allow(dead_code)]
-#[derive(Deserialize, Debug)]
-struct Offset {
-    #[serde(with = "stringify")]
-    size: usize,
-    #[serde(with = "stringify")]
-    offset: usize,
-}
-
-#[allow(dead_code)]
-#[derive(Deserialize, Debug)]
-struct RawChunk {
-    tx_path: Base64,
-    data_path: Base64,
-    chunk: Base64,
-}
 
 #[tokio::main]
 async fn main() {
@@ -26,7 +8,7 @@ async fn main() {
     let offset = reqwest::get(format!("https://arweave.net/tx/{}/offset", txid))
         .await
         .unwrap()
-        .json::<Offset>()
+        .json::<TransactionOffset>()
         .await
         .unwrap();
 
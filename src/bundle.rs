@@ -1,11 +1,21 @@
 //! Data structure and functionality to create, serialize and deserialize [`DataItem`]s.
 
 use crate::error::Error;
+use crate::signer::signature_lengths;
+use crate::status::{OutputFormat, QuietDisplay, VerboseDisplay};
 use crate::transaction::{Base64, DeepHashItem, Tag, ToItems};
 use avro_rs::Schema;
 use bytes::BufMut;
 use serde::{Deserialize, Serialize};
-use std::io::Write;
+use std::{
+    fmt,
+    io::{Read, Seek, SeekFrom, Take, Write},
+    path::PathBuf,
+};
+use tokio::{
+    fs,
+    io::{AsyncSeekExt, AsyncWriteExt},
+};
 
 /// Returns [`avro_rs::Schema`] for [`DataItem`] [`Tag`]s.
 pub fn get_tags_schema() -> Schema {
@@ -26,6 +36,17 @@ pub fn get_tags_schema() -> Schema {
     Schema::parse_str(schema).unwrap()
 }
 
+/// Maximum size of the serialized tags of a [`DataItem`].
+const MAX_TAG_BYTES: usize = 2048;
+
+/// Maximum size of the fields of a serialized [`DataItem`] preceding its data: signature type,
+/// signature, owner, optional target and anchor, tag counts and tags.
+const MAX_DATA_ITEM_HEADER_SIZE: usize = 2 + 512 + 512 + 33 + 33 + 16 + MAX_TAG_BYTES;
+
+/// Maximum number of bundles nested in each other, counting the outermost bundle, that get read
+/// when deserializing or verifying a bundle.
+pub const MAX_BUNDLE_DEPTH: usize = 16;
+
 /// Primary structure for [`DataItem`]s included in bundles.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct DataItem {
@@ -54,9 +75,50 @@ impl Default for DataItem {
     }
 }
 
+/// Options for the [`DataItem`]s created by [`crate::Arweave::create_data_item`].
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DataItemOptions {
+    /// Address the data items are sent to.
+    pub target: Option<Base64>,
+    /// Anchor included in each data item, unless `random_anchor` is set.
+    pub anchor: Option<Base64>,
+    /// Give each data item a different random anchor, so that identical data doesn't result in
+    /// identical data items.
+    pub random_anchor: bool,
+}
+
+impl DataItemOptions {
+    /// Sets the target address, which has to be 32 bytes.
+    pub fn target(mut self, target: Base64) -> Result<Self, Error> {
+        if target.0.len() != 32 {
+            return Err(Error::InvalidDataItemOption("target".to_string()));
+        }
+        self.target = Some(target);
+        Ok(self)
+    }
+
+    /// Sets an explicit anchor, which has to be 32 bytes.
+    pub fn anchor(mut self, anchor: Base64) -> Result<Self, Error> {
+        if anchor.0.len() != 32 {
+            return Err(Error::InvalidDataItemOption("anchor".to_string()));
+        }
+        self.anchor = Some(anchor);
+        self.random_anchor = false;
+        Ok(self)
+    }
+
+    /// Gives each data item a random 32 byte anchor.
+    pub fn random_anchor(mut self) -> Self {
+        self.anchor = None;
+        self.random_anchor = true;
+        self
+    }
+}
+
 impl DataItem {
     pub fn serialize(&self) -> Result<Vec<u8>, Error> {
-        if self.signature.0.len() != 512 {
+        let (signature_len, owner_len) = signature_lengths(self.signature_type)?;
+        if self.signature.0.len() != signature_len || self.owner.0.len() != owner_len {
             return Err(Error::UnsignedTransaction);
         }
         let mut buf = Vec::new().writer();
@@ -96,52 +158,49 @@ impl DataItem {
         Ok(buf.into_inner())
     }
     pub fn deserialize(bytes_vec: Vec<u8>) -> Result<Self, Error> {
-        let mut iter = bytes_vec.into_iter();
-        let mut data_item = DataItem::default();
+        Self::deserialize_slice(&bytes_vec)
+    }
 
-        let result = [(); 2].map(|_| iter.next().unwrap());
-        data_item.signature_type = u16::from_le_bytes(result);
-        if data_item.signature_type != 1 {
-            println!("invalid signature_type");
-            return Err(Error::InvalidDataItem);
-        }
+    /// Same as [`DataItem::deserialize`], without taking ownership of `bytes`.
+    pub fn deserialize_slice(mut bytes: &[u8]) -> Result<Self, Error> {
+        let data_item = Self::deserialize_header(&mut bytes)?;
+        Ok(DataItem {
+            data: Base64(bytes.to_vec()),
+            ..data_item
+        })
+    }
 
-        for _ in 0..512 {
-            data_item.signature.0.push(iter.next().unwrap());
-        }
+    /// Deserializes the fields of a data item preceding its data, advancing `bytes` to the
+    /// start of the data.
+    fn deserialize_header(bytes: &mut &[u8]) -> Result<Self, Error> {
+        let signature_type = u16::from_le_bytes(take(bytes, 2)?.try_into().unwrap());
+        let (signature_len, owner_len) =
+            signature_lengths(signature_type).map_err(|_| Error::InvalidDataItem)?;
 
-        for _ in 0..512 {
-            data_item.owner.0.push(iter.next().unwrap());
-        }
+        let signature = Base64(take(bytes, signature_len)?.to_vec());
+        let owner = Base64(take(bytes, owner_len)?.to_vec());
 
-        if iter.next().unwrap() == 1 {
-            for _ in 0..32 {
-                data_item.target.0.push(iter.next().unwrap());
-            }
-        }
+        let target = match take(bytes, 1)?[0] {
+            1 => Base64(take(bytes, 32)?.to_vec()),
+            _ => Base64::default(),
+        };
 
-        if iter.next().unwrap() == 1 {
-            for _ in 0..32 {
-                data_item.anchor.0.push(iter.next().unwrap());
-            }
-        }
+        let anchor = match take(bytes, 1)?[0] {
+            1 => Base64(take(bytes, 32)?.to_vec()),
+            _ => Base64::default(),
+        };
 
-        let number_of_tags = u64::from_le_bytes([(); 8].map(|_| iter.next().unwrap()));
-        let number_of_tag_bytes =
-            u64::from_le_bytes([(); 8].map(|_| iter.next().unwrap())) as usize;
-        if number_of_tag_bytes > 2048 {
+        let number_of_tags = u64::from_le_bytes(take(bytes, 8)?.try_into().unwrap());
+        let number_of_tag_bytes = u64::from_le_bytes(take(bytes, 8)?.try_into().unwrap()) as usize;
+        if number_of_tag_bytes > MAX_TAG_BYTES {
             return Err(Error::InvalidDataItem);
         }
 
-        data_item.tags = if number_of_tags > 0 {
+        let tags = if number_of_tags > 0 {
             let schema = get_tags_schema();
-            let mut reader = Vec::<u8>::with_capacity(number_of_tag_bytes);
-
-            for _ in 0..number_of_tag_bytes {
-                reader.push(iter.next().unwrap());
-            }
+            let mut reader = take(bytes, number_of_tag_bytes)?;
 
-            let value = avro_rs::from_avro_datum::<&[u8]>(&schema, &mut &*reader, None)?;
+            let value = avro_rs::from_avro_datum::<&[u8]>(&schema, &mut reader, None)?;
             let tags: Vec<Tag<String>> = avro_rs::from_value(&value)?;
             if tags.len() != number_of_tags as usize {
                 return Err(Error::InvalidDataItem);
@@ -151,9 +210,22 @@ impl DataItem {
             Vec::<Tag<String>>::new()
         };
 
-        data_item.data.0 = iter.collect();
+        Ok(DataItem {
+            signature_type,
+            signature,
+            owner,
+            target,
+            anchor,
+            tags,
+            ..DataItem::default()
+        })
+    }
 
-        Ok(data_item)
+    /// Returns true if the data item is tagged as containing a bundle, per ANS-104.
+    pub fn is_bundle(&self) -> bool {
+        let has_tag =
+            |name: &str, value: &str| self.tags.iter().any(|t| t.name == name && t.value == value);
+        has_tag("Bundle-Format", "binary") && has_tag("Bundle-Version", "2.0.0")
     }
 
     /// Header is 64 bytes with first 32 for the size of the bytes le. Second
@@ -171,6 +243,313 @@ impl DataItem {
     }
 }
 
+/// Writes a bundle to a file one [`DataItem`] at a time, so that only the data item being added
+/// has to be held in memory. Space for the header table is reserved when the file is created, so
+/// the number of data items has to be known up front, and the headers get written by
+/// [`BundleWriter::finish`].
+pub struct BundleWriter {
+    file: fs::File,
+    path: PathBuf,
+    number_of_data_items: usize,
+    headers: Vec<u8>,
+}
+
+impl BundleWriter {
+    pub async fn create(path: PathBuf, number_of_data_items: usize) -> Result<Self, Error> {
+        let mut file = fs::File::create(&path).await?;
+        file.seek(SeekFrom::Start(32 + 64 * number_of_data_items as u64))
+            .await?;
+        Ok(Self {
+            file,
+            path,
+            number_of_data_items,
+            headers: Vec::with_capacity(64 * number_of_data_items),
+        })
+    }
+
+    /// Appends a signed [`DataItem`] to the bundle.
+    pub async fn add_data_item(&mut self, data_item: &DataItem) -> Result<(), Error> {
+        let added = self.headers.len() / 64;
+        if added == self.number_of_data_items {
+            return Err(Error::BundleItemCount(added + 1, self.number_of_data_items));
+        }
+        let (header, binary) = data_item.to_bundle_item()?;
+        self.file.write_all(&binary).await?;
+        self.headers.extend(header);
+        Ok(())
+    }
+
+    /// Writes the header table, returning the path and size of the bundle.
+    pub async fn finish(mut self) -> Result<(PathBuf, u64), Error> {
+        let added = self.headers.len() / 64;
+        if added != self.number_of_data_items {
+            return Err(Error::BundleItemCount(added, self.number_of_data_items));
+        }
+        let size = self.file.stream_position().await?;
+        self.file.seek(SeekFrom::Start(0)).await?;
+        self.file.write_all(&(added as u64).to_le_bytes()).await?;
+        self.file.write_all(&[0u8; 24]).await?;
+        self.file.write_all(&self.headers).await?;
+        self.file.flush().await?;
+        Ok((self.path, size))
+    }
+}
+
+/// Splits `n` bytes off the front of `bytes`, returning [`Error::InvalidDataItem`] if there are
+/// fewer than `n` left.
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+    if bytes.len() < n {
+        return Err(Error::InvalidDataItem);
+    }
+    let (taken, rest) = bytes.split_at(n);
+    *bytes = rest;
+    Ok(taken)
+}
+
+/// Splits a serialized bundle into the id from the header and the binary of each of its
+/// [`DataItem`]s, returning [`Error::InvalidDataItem`] if the headers are inconsistent with the
+/// length of the bundle.
+pub fn split_bundle(bundle: &[u8]) -> Result<Vec<(Base64, &[u8])>, Error> {
+    let mut bytes = bundle;
+    let number_of_data_items =
+        u64::from_le_bytes(take(&mut bytes, 32)?[..8].try_into().unwrap()) as usize;
+
+    // Each header is 64 bytes, so guard against allocating for a bogus number of items.
+    if number_of_data_items > bytes.len() / 64 {
+        return Err(Error::InvalidDataItem);
+    }
+
+    let mut headers = Vec::with_capacity(number_of_data_items);
+    for _ in 0..number_of_data_items {
+        let bytes_len = u64::from_le_bytes(take(&mut bytes, 32)?[..8].try_into().unwrap());
+        let id = Base64(take(&mut bytes, 32)?.to_vec());
+        headers.push((id, bytes_len as usize));
+    }
+
+    headers
+        .into_iter()
+        .map(|(id, bytes_len)| Ok((id, take(&mut bytes, bytes_len)?)))
+        .collect()
+}
+
+/// Entry in the header table of a bundle, with the position of the [`DataItem`] in the bundle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BundleEntry {
+    pub id: Base64,
+    /// Offset of the data item from the start of the bundle.
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Fields of a [`DataItem`] read from a bundle without its data, with the position of the data
+/// in the bundle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataItemHeader {
+    /// Data item with its id from the header table and empty data.
+    pub data_item: DataItem,
+    /// Offset of the data from the start of the bundle.
+    pub data_offset: u64,
+    pub data_size: u64,
+}
+
+/// Reads a bundle lazily from a [`Read`] + [`Seek`], such as a file, so that large bundles can be
+/// scanned without reading them into memory. Entries in the header table are read one at a time
+/// with [`BundleReader::next_entry`], and data items are only read when requested. Truncated or
+/// malformed bundles return [`Error::InvalidDataItem`].
+pub struct BundleReader<R> {
+    reader: R,
+    bundle_size: u64,
+    number_of_data_items: u64,
+    next_index: u64,
+    next_offset: u64,
+}
+
+impl<R: Read + Seek> BundleReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let bundle_size = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        let number_of_data_items =
+            u64::from_le_bytes(read_exact(&mut reader, 32)?[..8].try_into().unwrap());
+
+        // Guard against a bogus number of items before computing offsets from it.
+        if number_of_data_items > (bundle_size - 32) / 64 {
+            return Err(Error::InvalidDataItem);
+        }
+
+        Ok(Self {
+            reader,
+            bundle_size,
+            number_of_data_items,
+            next_index: 0,
+            next_offset: 32 + 64 * number_of_data_items,
+        })
+    }
+
+    pub fn number_of_data_items(&self) -> u64 {
+        self.number_of_data_items
+    }
+
+    /// Reads the next entry from the header table, returning `None` after the last one.
+    pub fn next_entry(&mut self) -> Option<Result<BundleEntry, Error>> {
+        if self.next_index == self.number_of_data_items {
+            return None;
+        }
+        Some(self.read_entry())
+    }
+
+    fn read_entry(&mut self) -> Result<BundleEntry, Error> {
+        self.reader
+            .seek(SeekFrom::Start(32 + 64 * self.next_index))?;
+        let header = read_exact(&mut self.reader, 64)?;
+        let size = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let offset = self.next_offset;
+        if size > self.bundle_size - offset {
+            return Err(Error::InvalidDataItem);
+        }
+
+        self.next_index += 1;
+        self.next_offset += size;
+        Ok(BundleEntry {
+            id: Base64(header[32..].to_vec()),
+            offset,
+            size,
+        })
+    }
+
+    /// Reads all of the entries in the header table.
+    pub fn entries(&mut self) -> Result<Vec<BundleEntry>, Error> {
+        let mut entries = Vec::with_capacity(self.number_of_data_items as usize);
+        while let Some(entry) = self.next_entry() {
+            entries.push(entry?);
+        }
+        Ok(entries)
+    }
+
+    /// Reads the fields of a [`DataItem`] preceding its data.
+    pub fn read_header(&mut self, entry: &BundleEntry) -> Result<DataItemHeader, Error> {
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        let len = std::cmp::min(entry.size, MAX_DATA_ITEM_HEADER_SIZE as u64) as usize;
+        let buf = read_exact(&mut self.reader, len)?;
+        let mut bytes = &buf[..];
+        let mut data_item = DataItem::deserialize_header(&mut bytes)?;
+        data_item.id = entry.id.clone();
+
+        let header_size = (len - bytes.len()) as u64;
+        Ok(DataItemHeader {
+            data_item,
+            data_offset: entry.offset + header_size,
+            data_size: entry.size - header_size,
+        })
+    }
+
+    /// Returns a reader over the data of a [`DataItem`].
+    pub fn data_reader(&mut self, header: &DataItemHeader) -> Result<Take<&mut R>, Error> {
+        self.reader.seek(SeekFrom::Start(header.data_offset))?;
+        Ok((&mut self.reader).take(header.data_size))
+    }
+
+    /// Reads a whole [`DataItem`], including its data.
+    pub fn read_data_item(&mut self, entry: &BundleEntry) -> Result<DataItem, Error> {
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        let mut data_item =
+            DataItem::deserialize(read_exact(&mut self.reader, entry.size as usize)?)?;
+        data_item.id = entry.id.clone();
+        Ok(data_item)
+    }
+}
+
+/// Reads exactly `n` bytes, returning [`Error::InvalidDataItem`] if the reader ends first.
+fn read_exact<R: Read>(reader: &mut R, n: usize) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    reader.take(n as u64).read_to_end(&mut buf)?;
+    if buf.len() != n {
+        return Err(Error::InvalidDataItem);
+    }
+    Ok(buf)
+}
+
+/// Result of verifying a [`DataItem`] from a bundle against its own owner, as returned by
+/// [`crate::Arweave::verify_bundle`].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DataItemReport {
+    /// Id from the bundle header.
+    pub id: Base64,
+    /// Wallet address of the owner.
+    pub owner: Base64,
+    pub content_type: String,
+    pub data_size: u64,
+    pub tags: Vec<Tag<String>>,
+    /// Signature verifies against the deep hash of the data item with the owner's public key.
+    pub valid_signature: bool,
+    /// Id from the header is the SHA256 hash of the signature.
+    pub valid_id: bool,
+    /// Set if the data item binary, or a nested bundle it contains, could not be deserialized.
+    pub error: Option<String>,
+}
+
+impl DataItemReport {
+    pub fn is_valid(&self) -> bool {
+        self.valid_signature && self.valid_id && self.error.is_none()
+    }
+
+    pub fn header_string(output_format: &OutputFormat) -> String {
+        match output_format {
+            OutputFormat::Display => {
+                format!(
+                    " {:<43}  {:<43}  {:<24}  {:>9}  {}\n{:-<132}",
+                    "id", "owner", "content type", "bytes", "valid", ""
+                )
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+impl QuietDisplay for DataItemReport {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        if !self.is_valid() {
+            writeln!(w, "{}", self.id)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DataItemReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            " {:<43}  {:<43}  {:<24}  {:>9}  {}",
+            self.id,
+            self.owner,
+            self.content_type,
+            self.data_size,
+            self.is_valid(),
+        )?;
+        for tag in &self.tags {
+            writeln!(f, "   {}: {}", tag.name, tag.value)?;
+        }
+        Ok(())
+    }
+}
+
+impl VerboseDisplay for DataItemReport {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "{:<16} {}", "id:", self.id)?;
+        writeln!(w, "{:<16} {}", "owner:", self.owner)?;
+        writeln!(w, "{:<16} {}", "content_type:", self.content_type)?;
+        writeln!(w, "{:<16} {}", "data_size:", self.data_size)?;
+        for tag in &self.tags {
+            writeln!(w, "{:<16} {}: {}", "tag:", tag.name, tag.value)?;
+        }
+        writeln!(w, "{:<16} {}", "valid_signature:", self.valid_signature)?;
+        writeln!(w, "{:<16} {}", "valid_id:", self.valid_id)?;
+        if let Some(error) = &self.error {
+            writeln!(w, "{:<16} {}", "error:", error)?;
+        }
+        writeln!(w)
+    }
+}
+
 impl<'a> ToItems<'a, DataItem> for DataItem {
     fn to_deep_hash_item(&'a self) -> Result<DeepHashItem, Error> {
         let schema = get_tags_schema();
@@ -197,12 +576,15 @@ impl<'a> ToItems<'a, DataItem> for DataItem {
 
 #[cfg(test)]
 mod tests {
-    use super::DataItem;
+    use super::{BundleReader, DataItem};
     use crate::{
+        error::Error,
         status::Status,
         transaction::{Base64, FromUtf8Strs, Tag, ToItems},
         Arweave,
     };
+    use matches::assert_matches;
+    use std::io::{Cursor, Read};
     use std::path::PathBuf;
     use std::str::FromStr;
     use tokio::fs;
@@ -412,4 +794,56 @@ mod tests {
             1u16
         );
     }
+
+    #[tokio::test]
+    async fn test_bundle_reader() -> Result<(), Error> {
+        let arweave = Arweave::default();
+        let data_item = get_test_data_item().await;
+        let other_data_item = DataItem {
+            data: Base64(vec![1; 1000]),
+            tags: Vec::new(),
+            ..data_item.clone()
+        };
+        let statuses = [Status {
+            file_path: Some(PathBuf::from("file.name")),
+            ..Status::default()
+        }];
+        let (bundle, _) = arweave.create_bundle_from_data_items(vec![
+            (data_item.clone(), statuses[0].clone()),
+            (other_data_item.clone(), statuses[0].clone()),
+        ])?;
+
+        let mut reader = BundleReader::new(Cursor::new(bundle.clone()))?;
+        assert_eq!(reader.number_of_data_items(), 2);
+        let entries = reader.entries()?;
+        assert_eq!(entries[0].offset, 160);
+        assert_eq!(entries[1].offset, 160 + entries[0].size);
+        assert_eq!(reader.next_entry().map(|e| e.is_ok()), None);
+
+        // Data starts at the offset in the layout from test_data_items_to_bundle.
+        let header = reader.read_header(&entries[0])?;
+        assert_eq!(header.data_offset, 1418);
+        assert_eq!(header.data_size, 5);
+        assert_eq!(header.data_item.tags, data_item.tags);
+        assert!(header.data_item.data.0.is_empty());
+
+        let header = reader.read_header(&entries[1])?;
+        let mut data = Vec::new();
+        reader.data_reader(&header)?.read_to_end(&mut data)?;
+        assert_eq!(data, other_data_item.data.0);
+        assert_eq!(reader.read_data_item(&entries[0])?, data_item);
+
+        // Truncated bundles return errors instead of panicking.
+        for len in [0, 20, 100, 1000, bundle.len() - 1] {
+            let result =
+                BundleReader::new(Cursor::new(bundle[..len].to_vec())).and_then(|mut reader| {
+                    for entry in reader.entries()? {
+                        reader.read_header(&entry)?;
+                    }
+                    Ok(())
+                });
+            assert_matches!(result, Err(Error::InvalidDataItem));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,160 @@
+//! Configuration for the HTTP client shared by every network request.
+
+use crate::error::Error;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Proxy,
+};
+use std::{str::FromStr, time::Duration};
+use url::Url;
+
+/// Seconds to wait for a connection to be established before giving up.
+pub const DEFAULT_CONNECT_TIMEOUT: u64 = 30;
+
+/// Seconds to wait for a request to complete, including reading the response, before giving up.
+pub const DEFAULT_TIMEOUT: u64 = 300;
+
+/// Bytes per second that large uploads are given time to be sent at, on top of the timeout.
+pub const MIN_UPLOAD_RATE: u64 = 50_000;
+
+/// Options for the [`Client`] used by [`crate::Arweave`] for all of its requests, including
+/// those to bundlers, the price oracle and Solana, and for the headers added to requests to
+/// gateways and bundlers.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub connect_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    /// Proxy all requests are sent through. Credentials can be included in the url.
+    pub proxy: Option<Url>,
+    /// Headers added by [`crate::Arweave`] to requests to gateways and bundlers, but not to other
+    /// services, so that credentials for a gateway aren't sent elsewhere.
+    pub headers: HeaderMap,
+    pub user_agent: String,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Some(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT)),
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+            proxy: None,
+            headers: HeaderMap::new(),
+            user_agent: format!("arloader/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl ClientOptions {
+    /// Sets the connect timeout, with `None` waiting indefinitely.
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for complete requests, with `None` waiting indefinitely.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Adds a header to requests to gateways and bundlers, failing if `name` or `value` isn't
+    /// valid in a header.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self, Error> {
+        let name =
+            HeaderName::from_str(name).map_err(|_| Error::InvalidHeader(name.to_string()))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| Error::InvalidHeader(name.as_str().to_string()))?;
+        self.headers.append(name, value);
+        Ok(self)
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Returns the timeout for an upload with a body of `len` bytes, `timeout` extended by the
+    /// time to send the body at [`MIN_UPLOAD_RATE`], so that large uploads on slow connections
+    /// aren't cut off.
+    pub fn upload_timeout(&self, len: usize) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| timeout + Duration::from_secs(len as u64 / MIN_UPLOAD_RATE))
+    }
+
+    pub fn build(&self) -> Result<Client, Error> {
+        let mut builder = Client::builder().user_agent(&self.user_agent);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy.as_str())?);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientOptions, MIN_UPLOAD_RATE};
+    use crate::{error::Error, test_utils::serve, Arweave};
+    use matches::assert_matches;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_client_options() -> Result<(), Error> {
+        // Stand-in proxy that records the request it receives.
+        let (proxy_url, proxy) = serve(vec![("200 OK", String::new())]);
+
+        let options = ClientOptions::default()
+            .proxy(proxy_url)
+            .header("Authorization", "Bearer token")?
+            .user_agent("uploader/1.0");
+        let resp = options
+            .build()?
+            .get("http://oracle.invalid/price")
+            .send()
+            .await?;
+        assert!(resp.status().is_success());
+
+        // Headers are only added to requests to gateways and bundlers.
+        let request = proxy.join().unwrap().remove(0);
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.target, "http://oracle.invalid/price");
+        assert_eq!(request.header("authorization"), None);
+        assert_eq!(request.header("user-agent"), Some("uploader/1.0"));
+
+        let (gateway_url, gateway) = serve(vec![("200 OK", String::from("[]"))]);
+        let arweave = Arweave {
+            base_url: gateway_url,
+            ..Arweave::default()
+        }
+        .with_client_options(&ClientOptions {
+            proxy: None,
+            ..options.clone()
+        })?;
+        assert_eq!(arweave.get_pending_count().await?, 0);
+        let request = gateway.join().unwrap().remove(0);
+        assert_eq!(request.target, "/tx/pending");
+        assert_eq!(request.header("authorization"), Some("Bearer token"));
+
+        assert_eq!(
+            options.upload_timeout(10 * MIN_UPLOAD_RATE as usize),
+            options.timeout.map(|t| t + Duration::from_secs(10))
+        );
+        assert_eq!(options.timeout(None).upload_timeout(1), None);
+
+        assert_matches!(
+            ClientOptions::default().header("Bad Name", "value").err(),
+            Some(Error::InvalidHeader(_))
+        );
+        Ok(())
+    }
+}
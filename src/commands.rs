@@ -1,14 +1,19 @@
 //! Functions for Cli commands comprised of library functions.
 
 use crate::{
+    ar_to_winstons,
+    bundle::DataItemReport,
+    crypto::{generate_jwk, EncryptedKeypair, Provider},
     error::Error,
     file_stem_is_valid_txid,
+    graphql::{QueriedTransaction, TransactionsQuery},
+    query_transactions_stream,
     solana::{FLOOR, RATE, SOLANA_MAIN_URL, SOL_AR_BASE_URL},
     status::{OutputFormat, StatusCode},
-    transaction::{Base64, Tag},
+    transaction::{Base64, Tag, Transaction},
     update_bundle_statuses_stream, update_statuses_stream, upload_bundles_stream,
-    upload_bundles_stream_with_sol, upload_files_stream, upload_files_with_sol_stream, Arweave,
-    PathsChunk, BLOCK_SIZE, WINSTONS_PER_AR,
+    upload_bundles_stream_with_sol, upload_files_stream, upload_files_to_bundler_stream,
+    upload_files_with_sol_stream, Arweave, PathsChunk, BLOCK_SIZE, WINSTONS_PER_AR,
 };
 
 use futures::{
@@ -16,17 +21,153 @@ use futures::{
     StreamExt,
 };
 use glob::glob;
+use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
 use solana_sdk::signer::keypair;
 use std::{path::PathBuf, str::FromStr};
 use tokio::{
     fs,
+    io::AsyncWriteExt,
     time::{sleep, Duration},
 };
 use url::Url;
 
 pub type CommandResult = Result<(), Error>;
 
+/// Downloads and validates transaction data, writing it to `out`, or `<ID>` in the current
+/// directory if not provided.
+pub async fn command_download(arweave: &Arweave, id: &str, out: Option<PathBuf>) -> CommandResult {
+    let id = Base64::from_str(id)?;
+    let out = out.unwrap_or_else(|| PathBuf::from(id.to_string()));
+    let mut file = fs::File::create(&out).await?;
+
+    match arweave.download_transaction_data(&id, &mut file).await {
+        Ok(len) => {
+            println!(
+                "Downloaded and validated {} bytes of transaction {} to {}.",
+                len,
+                id,
+                out.display()
+            );
+            Ok(())
+        }
+        Err(e) => {
+            fs::remove_file(&out).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Opens a bundle from a local file if `bundle` is a file path, or otherwise downloads it to a
+/// temp file with `bundle` as the transaction id, returning the file and the path of the temp
+/// file to remove once done with it.
+async fn open_bundle(
+    arweave: &Arweave,
+    bundle: &str,
+) -> Result<(std::fs::File, Option<PathBuf>), Error> {
+    let path = PathBuf::from(bundle);
+    if path.is_file() {
+        return Ok((std::fs::File::open(path)?, None));
+    }
+    let id = Base64::from_str(bundle)?;
+    let temp_path = arweave.temp_bundle_path()?;
+    let result = async {
+        let mut file = fs::File::create(&temp_path).await?;
+        arweave.download_transaction_data(&id, &mut file).await?;
+        file.flush().await?;
+        Ok::<_, Error>(std::fs::File::open(&temp_path)?)
+    }
+    .await;
+    match result {
+        Ok(file) => Ok((file, Some(temp_path))),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path).await;
+            Err(e)
+        }
+    }
+}
+
+/// Writes the data of each item in a bundle to `out_dir`.
+pub async fn command_extract_bundle(
+    arweave: &Arweave,
+    bundle: &str,
+    out_dir: PathBuf,
+) -> CommandResult {
+    let (file, temp_path) = open_bundle(arweave, bundle).await?;
+    let extracted = arweave
+        .extract_bundle_from_reader(file, out_dir.clone())
+        .await;
+    if let Some(temp_path) = temp_path {
+        let _ = fs::remove_file(&temp_path).await;
+    }
+    let extracted = extracted?;
+    for (id, file_path) in &extracted {
+        println!(" {:<43}  {}", id, file_path.display());
+    }
+    println!(
+        "Extracted {} data items to {}.",
+        extracted.len(),
+        out_dir.display()
+    );
+    Ok(())
+}
+
+/// Prints the id, owner, content type, size and tags of each item in a bundle, and whether it
+/// verifies against its owner.
+pub async fn command_inspect_bundle(
+    arweave: &Arweave,
+    bundle: &str,
+    output_format: &OutputFormat,
+) -> CommandResult {
+    let (file, temp_path) = open_bundle(arweave, bundle).await?;
+    let reports = arweave.verify_bundle_from_reader(file);
+    if let Some(temp_path) = temp_path {
+        let _ = fs::remove_file(&temp_path).await;
+    }
+    let reports = reports?;
+
+    println!("{}", DataItemReport::header_string(output_format));
+    for report in &reports {
+        print!("{}", output_format.formatted_string(report));
+    }
+
+    let number_invalid = reports.iter().filter(|r| !r.is_valid()).count();
+    if number_invalid > 0 {
+        println!(
+            "{} of {} data items failed verification.",
+            number_invalid,
+            reports.len()
+        );
+    }
+    Ok(())
+}
+
+/// Prints transactions matching `query`, up to `limit` if provided.
+pub async fn command_query(
+    arweave: &Arweave,
+    query: TransactionsQuery,
+    limit: Option<usize>,
+    output_format: &OutputFormat,
+) -> CommandResult {
+    let mut stream =
+        Box::pin(query_transactions_stream(arweave, query).take(limit.unwrap_or(usize::MAX)));
+
+    let mut counter = 0;
+    while let Some(result) = stream.next().await {
+        let transaction = result?;
+        if counter == 0 {
+            println!("{}", QueriedTransaction::header_string(output_format));
+        }
+        print!("{}", output_format.formatted_string(&transaction));
+        counter += 1;
+    }
+
+    if counter == 0 {
+        println!("No transactions matched the filters provided.");
+    }
+    Ok(())
+}
+
 /// Gets cost of uploading a list of files.
 pub async fn command_files(paths: Option<Vec<PathBuf>>) -> CommandResult {
     println!("{:?}", paths);
@@ -161,6 +302,24 @@ pub async fn command_get_transaction(arweave: &Arweave, id: &str) -> CommandResu
     Ok(())
 }
 
+/// Fetches a transaction from the network and verifies its signature and id, printing the
+/// address of the wallet that signed it.
+pub async fn command_verify_transaction(arweave: &Arweave, id: &str) -> CommandResult {
+    let id = Base64::from_str(id)?;
+    let transaction = arweave.get_transaction(&id).await?;
+    if transaction.id != id {
+        return Err(Error::TransactionIdMismatch);
+    }
+    transaction.verify()?;
+
+    let owner_address = Base64(arweave.crypto.hash_sha256(&transaction.owner.0)?.to_vec());
+    println!(
+        "Transaction {} is valid, with a format {} signature by wallet {}.",
+        id, transaction.format, owner_address
+    );
+    Ok(())
+}
+
 /// Lists transaction statuses, filtered by statuses and max confirmations if provided.
 pub async fn command_list_statuses<IP>(
     arweave: &Arweave,
@@ -418,6 +577,54 @@ where
     Ok(())
 }
 
+/// Uploads files to a bundler as individual data items.
+pub async fn command_upload_to_bundler<IP>(
+    arweave: &Arweave,
+    paths_iter: IP,
+    log_dir: Option<PathBuf>,
+    tags: Option<Vec<Tag<String>>>,
+    bundler_url: Url,
+    output_format: &OutputFormat,
+    buffer: usize,
+) -> CommandResult
+where
+    IP: Iterator<Item = PathBuf> + Send + Sync,
+{
+    let mut stream = upload_files_to_bundler_stream(
+        arweave,
+        paths_iter,
+        tags.unwrap_or_default(),
+        log_dir.clone(),
+        &bundler_url,
+        buffer,
+    );
+
+    let mut counter = 0;
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(status) => {
+                if counter == 0 {
+                    if let Some(log_dir) = &log_dir {
+                        println!("Logging statuses to {}", &log_dir.display());
+                    }
+                    println!("{}", status.header_string(output_format));
+                }
+                print!("{}", output_format.formatted_string(&status));
+                counter += 1;
+            }
+            Err(e) => println!("{:#?}", e),
+        }
+    }
+
+    if counter == 0 {
+        println!("<FILE_PATHS> didn't match any files.");
+    } else {
+        println!("Uploaded {} files to {}.", counter, bundler_url);
+    }
+
+    Ok(())
+}
+
 /// Uploads bundles created from provided glob to Arweave.
 pub async fn command_upload_bundles(
     arweave: &Arweave,
@@ -572,6 +779,96 @@ pub async fn command_upload_bundles_with_sol(
     Ok(())
 }
 
+/// Resumes a chunked upload, posting only the chunks missing from the uploader state in `log_dir`.
+pub async fn command_resume(
+    arweave: &Arweave,
+    id: &str,
+    log_dir: PathBuf,
+    buffer: usize,
+) -> CommandResult {
+    let id = Base64::from_str(id)?;
+    let uploader = arweave.resume_upload(&id, log_dir.clone(), buffer).await?;
+    println!(
+        "Posted {} of {} chunks for transaction {}. Run `arloader get-status {}` to confirm transaction.",
+        uploader.posted_chunks.len(),
+        uploader.number_of_chunks,
+        id,
+        id,
+    );
+    Ok(())
+}
+
+/// Creates an unsigned transaction for the file at `file_path` and writes it to `out` as json,
+/// to be signed with [`command_tx_sign`] on a machine that holds the keypair.
+pub async fn command_tx_create(
+    arweave: &Arweave,
+    file_path: PathBuf,
+    out: PathBuf,
+    tags: Option<Vec<Tag<Base64>>>,
+    reward_mult: f32,
+) -> CommandResult {
+    let price_terms = arweave.get_price_terms(reward_mult).await?;
+    let mut transaction = arweave
+        .create_transaction_from_file_path_streaming(
+            file_path.clone(),
+            tags,
+            None,
+            price_terms,
+            true,
+        )
+        .await?;
+    // The owner is set when signing, since the keypair isn't available here.
+    transaction.owner = Base64::default();
+    fs::write(&out, serde_json::to_string_pretty(&transaction)?).await?;
+
+    println!(
+        "Wrote unsigned transaction for {} to {}, with a reward of {} {}.",
+        file_path.display(),
+        out.display(),
+        transaction.reward,
+        arweave.units
+    );
+    Ok(())
+}
+
+/// Signs the transaction written to `tx_path` by [`command_tx_create`], without accessing the
+/// network, and writes it to `out`.
+pub async fn command_tx_sign(arweave: &Arweave, tx_path: PathBuf, out: PathBuf) -> CommandResult {
+    let mut transaction: Transaction = serde_json::from_str(&fs::read_to_string(&tx_path).await?)?;
+    transaction.owner = arweave.transaction_signer()?.owner()?;
+    let signed_transaction = arweave.sign_transaction(transaction)?;
+    fs::write(&out, serde_json::to_string_pretty(&signed_transaction)?).await?;
+
+    println!(
+        "Signed transaction {} and wrote it to {}.",
+        signed_transaction.id,
+        out.display()
+    );
+    Ok(())
+}
+
+/// Posts the signed transaction written to `tx_path` by [`command_tx_sign`] along with the chunks
+/// of the file at `file_path` it was created from.
+pub async fn command_tx_post(
+    arweave: &Arweave,
+    tx_path: PathBuf,
+    file_path: PathBuf,
+    log_dir: Option<PathBuf>,
+    buffer: usize,
+) -> CommandResult {
+    let signed_transaction: Transaction =
+        serde_json::from_str(&fs::read_to_string(&tx_path).await?)?;
+    let (id, reward) = arweave
+        .post_signed_transaction_from_file(signed_transaction, file_path, buffer, log_dir)
+        .await?;
+
+    println!(
+        "Posted transaction {} with a reward of {} {}. Run `arloader get-status {}` to confirm transaction.",
+        id, reward, arweave.units, id
+    );
+    Ok(())
+}
+
 /// Re-uploads files from status and max confirmations criteria.
 pub async fn command_reupload<IP>(
     arweave: &Arweave,
@@ -669,6 +966,11 @@ where
     let missing_paths_iter =
         paths_iter.filter(|p| !all_paths_map.contains_key(&p.display().to_string()));
 
+    // Files found already uploaded have no bundle to re-upload.
+    let all_statuses = all_statuses
+        .into_iter()
+        .filter(|s| !s.deduplicated)
+        .collect();
     let filtered_statuses = arweave.filter_statuses(all_statuses, statuses, max_confirms)?;
     let mut bundle_status_paths = Vec::new();
 
@@ -970,6 +1272,106 @@ where
     Ok(())
 }
 
+/// Environment variable read for keypair file passphrases before prompting for them.
+pub const KEYPAIR_PASSPHRASE_ENV: &str = "AR_KEYPAIR_PASSPHRASE";
+
+/// Gets the passphrase for an encrypted keypair file from [`KEYPAIR_PASSPHRASE_ENV`] if set, or
+/// otherwise by prompting for it, twice if `confirm` is true.
+pub fn get_keypair_passphrase(confirm: bool) -> Result<String, Error> {
+    if let Ok(passphrase) = std::env::var(KEYPAIR_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    let passphrase = rpassword::prompt_password_stderr("Keypair passphrase: ")?;
+    if confirm && passphrase != rpassword::prompt_password_stderr("Confirm passphrase: ")? {
+        return Err(Error::PassphraseMismatch);
+    }
+    Ok(passphrase)
+}
+
+/// Writes `contents` to a new keypair file at `path`, failing if it already exists.
+async fn write_keypair_file(path: &PathBuf, contents: &str) -> Result<(), Error> {
+    // Never overwrite an existing keypair, and keep the new one private to the user.
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path).await?;
+    file.write_all(contents.as_bytes()).await?;
+    Ok(())
+}
+
+/// Generates a new Arweave keypair and writes it to `out`, or to `arweave-keyfile-<ADDRESS>.json`
+/// in the current directory if not provided, printing the wallet address. The keypair is
+/// encrypted with `passphrase` if provided.
+pub async fn command_wallet_new(out: Option<PathBuf>, passphrase: Option<String>) -> CommandResult {
+    let jwk = generate_jwk()?;
+    let address = Provider::from_jwk_str(&jwk)?.wallet_address()?;
+    let out = out.unwrap_or_else(|| PathBuf::from(format!("arweave-keyfile-{}.json", address)));
+
+    let contents = match passphrase {
+        Some(passphrase) => serde_json::to_string(&EncryptedKeypair::encrypt(&jwk, &passphrase)?)?,
+        None => jwk,
+    };
+    write_keypair_file(&out, &contents).await?;
+
+    eprintln!("Wrote keypair for new wallet to {}.", out.display());
+    println!("{}", address);
+    Ok(())
+}
+
+/// Encrypts the keypair file at `keypair_path` with `passphrase`, writing it to `out`, printing
+/// the wallet address.
+pub async fn command_wallet_encrypt(
+    keypair_path: PathBuf,
+    out: PathBuf,
+    passphrase: String,
+) -> CommandResult {
+    let jwk = fs::read_to_string(&keypair_path).await?;
+    let address = Provider::from_jwk_str(&jwk)?.wallet_address()?;
+    let encrypted = EncryptedKeypair::encrypt(&jwk, &passphrase)?;
+    write_keypair_file(&out, &serde_json::to_string(&encrypted)?).await?;
+
+    eprintln!(
+        "Wrote encrypted keypair for {} to {}. Remove the plaintext keypair at {} once you've confirmed it loads.",
+        address,
+        out.display(),
+        keypair_path.display()
+    );
+    println!("{}", address);
+    Ok(())
+}
+
+/// Transfers `ar_amount` AR to the wallet at `target`, checking that the wallet balance covers
+/// the amount and the reward before posting.
+pub async fn command_transfer(arweave: &Arweave, target: &str, ar_amount: &str) -> CommandResult {
+    let quantity = ar_to_winstons(ar_amount)?;
+    let target = Base64::from_str(target)?;
+    let (balance, transaction) = try_join(
+        arweave.get_wallet_balance(None),
+        arweave.create_transfer_transaction(target.clone(), quantity, Vec::new(), None, None, None),
+    )
+    .await?;
+
+    let required = quantity
+        .checked_add(transaction.reward)
+        .ok_or_else(|| Error::InvalidArAmount(ar_amount.to_string()))?;
+    if balance < BigUint::from(required) {
+        return Err(Error::InsufficientArFunds(required));
+    }
+
+    let signed_transaction = arweave.sign_transaction(transaction)?;
+    let (id, reward) = arweave.post_transaction(&signed_transaction).await?;
+    println!(
+        "Posted transaction {} transferring {} {units} to {} with a reward of {} {units}.",
+        id,
+        quantity,
+        target,
+        reward,
+        units = arweave.units
+    );
+    Ok(())
+}
+
 /// Gets balance for provided wallet address.
 pub async fn command_wallet_balance(
     arweave: &Arweave,
@@ -2,17 +2,24 @@
 
 use crate::{
     error::Error,
-    transaction::{Base64, DeepHashItem},
+    signer::{verify_signature, SIGNATURE_TYPE_ARWEAVE},
+    transaction::{Base64, DeepHashItem, ToItems, Transaction},
 };
+use ::rand::rngs::OsRng;
 use jsonwebkey::JsonWebKey;
 use log::debug;
 use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
     digest::{Context, SHA256, SHA384},
+    pbkdf2,
     rand::{self, SecureRandom},
     signature::{self, KeyPair, RsaKeyPair},
 };
+use rsa::{BigUint, PublicKeyParts, RsaPrivateKey};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::fs as fsSync;
-use std::path::PathBuf;
+use std::{num::NonZeroU32, path::PathBuf};
 use tokio::fs;
 
 /// Struct for for crypto methods.
@@ -34,25 +41,51 @@ impl Default for Provider {
 impl Provider {
     /// Reads a [`JsonWebKey`] from a [`PathBuf`] and stores it as a [`signature::RsaKeyPair`] in
     /// the `keypair` property of [`Provider`] for future use in signing and funding transactions.
+    /// Returns [`Error::PassphraseRequired`] if the keypair file is encrypted.
     pub async fn from_keypair_path(keypair_path: PathBuf) -> Result<Provider, Error> {
         debug!("{:?}", keypair_path);
         let data = fs::read_to_string(keypair_path).await?;
+        Self::from_keypair_str(&data, None)
+    }
+
+    /// Reads a keypair file encrypted with [`EncryptedKeypair`], decrypting it with `passphrase`.
+    /// Plaintext keypair files are read as with [`Provider::from_keypair_path`].
+    pub async fn from_encrypted_keypair_path(
+        keypair_path: PathBuf,
+        passphrase: &str,
+    ) -> Result<Provider, Error> {
+        debug!("{:?}", keypair_path);
+        let data = fs::read_to_string(keypair_path).await?;
+        Self::from_keypair_str(&data, Some(passphrase))
+    }
 
-        let jwk_parsed: JsonWebKey = data.parse().unwrap();
+    /// Creates a [`Provider`] from a [`JsonWebKey`] string, as read from a keypair file.
+    pub fn from_jwk_str(jwk: &str) -> Result<Provider, Error> {
+        let jwk_parsed: JsonWebKey = jwk
+            .parse()
+            .map_err(|e: jsonwebkey::Error| Error::MalformedJwk(e.to_string()))?;
         Ok(Self {
             keypair: signature::RsaKeyPair::from_pkcs8(&jwk_parsed.key.as_ref().to_der())?,
             sr: rand::SystemRandom::new(),
         })
     }
+
     /// Sync version of [`Provider::from_keypair_path`].
     pub fn from_keypair_path_sync(keypair_path: PathBuf) -> Result<Provider, Error> {
         let data = fsSync::read_to_string(keypair_path)?;
+        Self::from_keypair_str(&data, None)
+    }
 
-        let jwk_parsed: JsonWebKey = data.parse().unwrap();
-        Ok(Self {
-            keypair: signature::RsaKeyPair::from_pkcs8(&jwk_parsed.key.as_ref().to_der())?,
-            sr: rand::SystemRandom::new(),
-        })
+    /// Creates a [`Provider`] from the contents of a keypair file, decrypting them with
+    /// `passphrase` if they are an [`EncryptedKeypair`].
+    fn from_keypair_str(data: &str, passphrase: Option<&str>) -> Result<Provider, Error> {
+        match serde_json::from_str::<EncryptedKeypair>(data) {
+            Ok(encrypted) => {
+                let passphrase = passphrase.ok_or(Error::PassphraseRequired)?;
+                Self::from_jwk_str(&encrypted.decrypt(passphrase)?)
+            }
+            Err(_) => Self::from_jwk_str(data),
+        }
     }
 
     /// Returns the full modulus of the stored keypair. Encoded as a Base64Url String,
@@ -128,20 +161,19 @@ impl Provider {
         Ok(())
     }
 
-    pub fn hash_sha256(&self, message: &[u8]) -> Result<[u8; 32], Error> {
-        let mut context = Context::new(&SHA256);
-        context.update(message);
-        let mut result: [u8; 32] = [0; 32];
-        result.copy_from_slice(context.finish().as_ref());
-        Ok(result)
+    /// Verifies that a message was signed by the keypair with RSA modulus `owner`, as included
+    /// in transactions and data items, rather than by the keypair of the Provider.
+    pub fn verify_with_owner(
+        &self,
+        owner: &[u8],
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<(), Error> {
+        verify_signature(SIGNATURE_TYPE_ARWEAVE, owner, signature, message)
     }
 
-    fn hash_sha384(&self, message: &[u8]) -> Result<[u8; 48], Error> {
-        let mut context = Context::new(&SHA384);
-        context.update(message);
-        let mut result: [u8; 48] = [0; 48];
-        result.copy_from_slice(context.finish().as_ref());
-        Ok(result)
+    pub fn hash_sha256(&self, message: &[u8]) -> Result<[u8; 32], Error> {
+        Ok(hash_sha256(message))
     }
 
     /// Returns a SHA256 hash of the the concatenated SHA256 hashes of a vector of messages.
@@ -156,51 +188,194 @@ impl Provider {
         Ok(hash)
     }
 
-    /// Returns a SHA384 hash of the the concatenated SHA384 hashes of a vector messages.
-    fn hash_all_sha384(&self, messages: Vec<&[u8]>) -> Result<[u8; 48], Error> {
-        let hash: Vec<u8> = messages
-            .into_iter()
-            .map(|m| self.hash_sha384(m).unwrap())
-            .into_iter()
-            .flatten()
-            .collect();
-        let hash = self.hash_sha384(&hash)?;
-        Ok(hash)
+    /// Calculates the deep hash of `deep_hash_item` with [`deep_hash`].
+    pub fn deep_hash(&self, deep_hash_item: DeepHashItem) -> Result<[u8; 48], Error> {
+        Ok(deep_hash(deep_hash_item))
     }
 
-    /// Concatenates two `[u8; 48]` arrays, returning a `[u8; 96]` array.
-    fn concat_u8_48(&self, left: [u8; 48], right: [u8; 48]) -> Result<[u8; 96], Error> {
-        let mut iter = left.into_iter().chain(right);
-        let result = [(); 96].map(|_| iter.next().unwrap());
-        Ok(result)
+    /// Returns the message signed for `transaction` with [`transaction_signature_data`].
+    pub fn transaction_signature_data(&self, transaction: &Transaction) -> Result<Vec<u8>, Error> {
+        transaction_signature_data(transaction)
     }
 
-    /// Calculates data root of transaction in accordance with implementation in [arweave-js](https://github.com/ArweaveTeam/arweave-js/blob/master/src/common/lib/deepHash.ts).
-    /// [`DeepHashItem`] is a recursive Enum that allows the function to be applied to
-    /// nested [`Vec<u8>`] of arbitrary depth.
-    pub fn deep_hash(&self, deep_hash_item: DeepHashItem) -> Result<[u8; 48], Error> {
-        let hash = match deep_hash_item {
-            DeepHashItem::Blob(blob) => {
-                let blob_tag = format!("blob{}", blob.len());
-                self.hash_all_sha384(vec![blob_tag.as_bytes(), &blob])?
-            }
-            DeepHashItem::List(list) => {
-                let list_tag = format!("list{}", list.len());
-                let mut hash = self.hash_sha384(list_tag.as_bytes())?;
+    pub fn fill_rand(&self, dest: &mut [u8]) -> Result<(), Error> {
+        let rand_bytes = self.sr.fill(dest)?;
+        Ok(rand_bytes)
+    }
+}
 
-                for child in list.into_iter() {
-                    let child_hash = self.deep_hash(child)?;
-                    hash = self.hash_sha384(&self.concat_u8_48(hash, child_hash)?)?;
-                }
-                hash
+/// Returns the SHA256 hash of `message`.
+pub fn hash_sha256(message: &[u8]) -> [u8; 32] {
+    let mut context = Context::new(&SHA256);
+    context.update(message);
+    let mut result: [u8; 32] = [0; 32];
+    result.copy_from_slice(context.finish().as_ref());
+    result
+}
+
+fn hash_sha384(message: &[u8]) -> [u8; 48] {
+    let mut context = Context::new(&SHA384);
+    context.update(message);
+    let mut result: [u8; 48] = [0; 48];
+    result.copy_from_slice(context.finish().as_ref());
+    result
+}
+
+/// Returns a SHA384 hash of the the concatenated SHA384 hashes of a vector messages.
+fn hash_all_sha384(messages: Vec<&[u8]>) -> [u8; 48] {
+    let hash: Vec<u8> = messages.into_iter().flat_map(hash_sha384).collect();
+    hash_sha384(&hash)
+}
+
+/// Concatenates two `[u8; 48]` arrays, returning a `[u8; 96]` array.
+fn concat_u8_48(left: [u8; 48], right: [u8; 48]) -> [u8; 96] {
+    let mut iter = left.into_iter().chain(right);
+    [(); 96].map(|_| iter.next().unwrap())
+}
+
+/// Calculates data root of transaction in accordance with implementation in [arweave-js](https://github.com/ArweaveTeam/arweave-js/blob/master/src/common/lib/deepHash.ts).
+/// [`DeepHashItem`] is a recursive Enum that allows the function to be applied to
+/// nested [`Vec<u8>`] of arbitrary depth.
+pub fn deep_hash(deep_hash_item: DeepHashItem) -> [u8; 48] {
+    match deep_hash_item {
+        DeepHashItem::Blob(blob) => {
+            let blob_tag = format!("blob{}", blob.len());
+            hash_all_sha384(vec![blob_tag.as_bytes(), &blob])
+        }
+        DeepHashItem::List(list) => {
+            let list_tag = format!("list{}", list.len());
+            let mut hash = hash_sha384(list_tag.as_bytes());
+
+            for child in list.into_iter() {
+                let child_hash = deep_hash(child);
+                hash = hash_sha384(&concat_u8_48(hash, child_hash));
             }
-        };
-        Ok(hash)
+            hash
+        }
+    }
+}
+
+/// Returns the message signed for `transaction`, the concatenation of its fields for format 1
+/// transactions or the deep hash of its fields for format 2 transactions.
+pub fn transaction_signature_data(transaction: &Transaction) -> Result<Vec<u8>, Error> {
+    match transaction.format {
+        1 => Ok(transaction.format_1_signature_data()),
+        2 => Ok(deep_hash(transaction.to_deep_hash_item()?).to_vec()),
+        format => Err(Error::UnsupportedTransactionFormat(format)),
     }
+}
 
-    pub fn fill_rand(&self, dest: &mut [u8]) -> Result<(), Error> {
-        let rand_bytes = self.sr.fill(dest)?;
-        Ok(rand_bytes)
+/// Generates a new 4096 bit RSA keypair, returning it as a [`JsonWebKey`] string that can be
+/// written to a keypair file and read with [`Provider::from_keypair_path`].
+pub fn generate_jwk() -> Result<String, Error> {
+    let key = RsaPrivateKey::new(&mut OsRng, 4096)?;
+    let (p, q) = (&key.primes()[0], &key.primes()[1]);
+    let encode = |n: &BigUint| Base64(n.to_bytes_be()).to_string();
+    let jwk = json!({
+        "kty": "RSA",
+        "n": encode(key.n()),
+        "e": encode(key.e()),
+        "d": encode(key.d()),
+        "p": encode(p),
+        "q": encode(q),
+        "dp": encode(&(key.d() % (p - 1u8))),
+        "dq": encode(&(key.d() % (q - 1u8))),
+        "qi": encode(&key.crt_coefficient().ok_or(rsa::errors::Error::InvalidCoefficient)?),
+    });
+    Ok(jwk.to_string())
+}
+
+/// Key derivation function used for [`EncryptedKeypair`]s.
+pub const KEYPAIR_KDF: &str = "pbkdf2-hmac-sha256";
+/// Cipher used for [`EncryptedKeypair`]s.
+pub const KEYPAIR_CIPHER: &str = "aes-256-gcm";
+/// Number of PBKDF2 iterations used to derive keys for new [`EncryptedKeypair`]s.
+pub const KEYPAIR_KDF_ITERATIONS: u32 = 600_000;
+/// Fewest PBKDF2 iterations accepted when decrypting an [`EncryptedKeypair`].
+pub const MIN_KEYPAIR_KDF_ITERATIONS: u32 = 100_000;
+/// Most PBKDF2 iterations accepted when decrypting an [`EncryptedKeypair`], so that a keypair file
+/// can't make key derivation run for hours.
+pub const MAX_KEYPAIR_KDF_ITERATIONS: u32 = 10_000_000;
+
+/// Keypair file contents with a [`JsonWebKey`] sealed with AES-256-GCM, using a key derived from a
+/// passphrase with PBKDF2-HMAC-SHA256.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EncryptedKeypair {
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: Base64,
+    pub cipher: String,
+    pub nonce: Base64,
+    pub ciphertext: Base64,
+}
+
+impl EncryptedKeypair {
+    /// Encrypts `jwk` with a key derived from `passphrase` and a random salt.
+    pub fn encrypt(jwk: &str, passphrase: &str) -> Result<Self, Error> {
+        let sr = rand::SystemRandom::new();
+        let mut salt = [0u8; 16];
+        sr.fill(&mut salt)?;
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        sr.fill(&mut nonce)?;
+
+        let key = Self::derive_key(passphrase, &salt, KEYPAIR_KDF_ITERATIONS)?;
+        let mut ciphertext = jwk.as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut ciphertext,
+        )?;
+
+        Ok(Self {
+            kdf: KEYPAIR_KDF.to_string(),
+            iterations: KEYPAIR_KDF_ITERATIONS,
+            salt: Base64(salt.to_vec()),
+            cipher: KEYPAIR_CIPHER.to_string(),
+            nonce: Base64(nonce.to_vec()),
+            ciphertext: Base64(ciphertext),
+        })
+    }
+
+    /// Decrypts the [`JsonWebKey`] string, returning [`Error::WrongPassphrase`] if it can't be
+    /// authenticated with the key derived from `passphrase`. Returns
+    /// [`Error::UnsupportedKeypairEncryption`] if the file's iteration count is outside
+    /// [`MIN_KEYPAIR_KDF_ITERATIONS`] and [`MAX_KEYPAIR_KDF_ITERATIONS`].
+    pub fn decrypt(&self, passphrase: &str) -> Result<String, Error> {
+        if self.kdf != KEYPAIR_KDF || self.cipher != KEYPAIR_CIPHER {
+            return Err(Error::UnsupportedKeypairEncryption(format!(
+                "{} with {}",
+                self.cipher, self.kdf
+            )));
+        }
+        if !(MIN_KEYPAIR_KDF_ITERATIONS..=MAX_KEYPAIR_KDF_ITERATIONS).contains(&self.iterations) {
+            return Err(Error::UnsupportedKeypairEncryption(format!(
+                "{} with {} iterations",
+                self.kdf, self.iterations
+            )));
+        }
+        let nonce = Nonce::try_assume_unique_for_key(&self.nonce.0)
+            .map_err(|_| Error::MalformedJwk("invalid nonce".to_string()))?;
+
+        let key = Self::derive_key(passphrase, &self.salt.0, self.iterations)?;
+        let mut in_out = self.ciphertext.0.clone();
+        let jwk = key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| Error::WrongPassphrase)?;
+        String::from_utf8(jwk.to_vec()).map_err(|e| Error::MalformedJwk(e.to_string()))
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, Error> {
+        let iterations = NonZeroU32::new(iterations)
+            .ok_or_else(|| Error::MalformedJwk("kdf iterations must be non-zero".to_string()))?;
+        let mut key = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations,
+            salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        Ok(LessSafeKey::new(UnboundKey::new(&aead::AES_256_GCM, &key)?))
     }
 }
 
@@ -218,11 +393,15 @@ const DEFAULT_KEYPAIR: &str = r##"{
 
 #[cfg(test)]
 mod tests {
-    use super::Provider;
+    use super::{
+        generate_jwk, EncryptedKeypair, Provider, MAX_KEYPAIR_KDF_ITERATIONS,
+        MIN_KEYPAIR_KDF_ITERATIONS,
+    };
     use crate::{
         Arweave, Error,
         {transaction::Transaction, ToItems},
     };
+    use matches::assert_matches;
     use std::path::PathBuf;
     use std::str::FromStr;
     use url::Url;
@@ -262,4 +441,73 @@ mod tests {
             "jA6UzKJ1cIvL2vUIct7Qf90QhC5b1UttvwknaGGBtjI"
         );
     }
+
+    #[tokio::test]
+    async fn test_generate_jwk() -> Result<(), Error> {
+        let jwk = generate_jwk()?;
+        let temp_dir = tempdir::TempDir::new("test_generate_jwk")?;
+        let keypair_path = temp_dir.path().join("keypair.json");
+        tokio::fs::write(&keypair_path, &jwk).await?;
+
+        let provider = Provider::from_keypair_path(keypair_path).await?;
+        assert_eq!(
+            provider.wallet_address()?,
+            Provider::from_jwk_str(&jwk)?.wallet_address()?
+        );
+
+        let message = b"hello";
+        let signature = provider.sign(message)?;
+        provider.verify(&signature, message)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_keypair() -> Result<(), Error> {
+        let keypair_path = PathBuf::from(
+            "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+        );
+        let jwk = tokio::fs::read_to_string(&keypair_path).await?;
+        let address = Provider::from_keypair_path(keypair_path)
+            .await?
+            .wallet_address()?;
+
+        let encrypted = EncryptedKeypair::encrypt(&jwk, "correct horse")?;
+        let temp_dir = tempdir::TempDir::new("test_encrypted_keypair")?;
+        let encrypted_path = temp_dir.path().join("keypair.json");
+        tokio::fs::write(&encrypted_path, serde_json::to_string(&encrypted)?).await?;
+
+        let provider =
+            Provider::from_encrypted_keypair_path(encrypted_path.clone(), "correct horse").await?;
+        assert_eq!(provider.wallet_address()?, address);
+
+        assert_matches!(
+            Provider::from_encrypted_keypair_path(encrypted_path.clone(), "battery staple")
+                .await
+                .err(),
+            Some(Error::WrongPassphrase)
+        );
+        assert_matches!(
+            Provider::from_keypair_path(encrypted_path).await.err(),
+            Some(Error::PassphraseRequired)
+        );
+        for iterations in [
+            0,
+            MIN_KEYPAIR_KDF_ITERATIONS - 1,
+            MAX_KEYPAIR_KDF_ITERATIONS + 1,
+        ] {
+            assert_matches!(
+                EncryptedKeypair {
+                    iterations,
+                    ..encrypted.clone()
+                }
+                .decrypt("correct horse"),
+                Err(Error::UnsupportedKeypairEncryption(_))
+            );
+        }
+        assert_matches!(
+            Provider::from_jwk_str(r#"{"kty": "RSA"}"#).err(),
+            Some(Error::MalformedJwk(_))
+        );
+        Ok(())
+    }
 }
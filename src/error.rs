@@ -21,32 +21,62 @@ pub enum Error {
     AvroDeError(#[from] avro_rs::DeError),
     #[error("base64 decode: {0}")]
     Base64Decode(#[from] DecodeError),
+    #[error("bundle has {0} data items, expected {1}")]
+    BundleItemCount(usize, usize),
+    #[error("bundles nested more than {0} deep")]
+    BundleTooDeep(usize),
+    #[error("error posting to bundler: {0}")]
+    BundlerPostError(reqwest::StatusCode),
     #[error("bincode: {0}")]
     Bincode(#[from] Box<bincode::ErrorKind>),
     #[error("unhandled boxed dyn error {0}")]
     BoxedDynStd(#[from] Box<dyn std::error::Error>),
+    #[error("file data does not match transaction data root")]
+    DataRootMismatch,
+    #[error("data does not match transaction data size")]
+    DataSizeMismatch,
+    #[error("external signer: {0}")]
+    ExternalSigner(String),
     #[error("formatting error")]
     FormatError(#[from] std::fmt::Error),
     #[error("from utf8: {0}")]
     FromUtf8(#[from] FromUtf8Error),
     #[error("glob patters: {0}")]
     GlobPattern(#[from] glob::PatternError),
+    #[error("graphql: {0}")]
+    Graphql(String),
+    #[error("hex decode: {0}")]
+    HexDecode(#[from] hex::FromHexError),
+    #[error("invalid ar amount: {0}")]
+    InvalidArAmount(String),
     #[error("invalid bunlde item binary")]
     InvalidDataItem,
+    #[error("invalid data item {0}, must be 32 bytes")]
+    InvalidDataItemOption(String),
     #[error("hashing failed")]
     InvalidHash,
     #[error("invalid proof")]
     InvalidProof,
+    #[error("invalid signature")]
+    InvalidSignature,
     #[error("invalid tags")]
     InvalidTags,
+    #[error("{1} chunk(s) of transaction {0} not posted, run `arloader resume {0}` to retry")]
+    IncompleteUpload(String, usize),
+    #[error("insufficient ar funds, {0} winstons required")]
+    InsufficientArFunds(u64),
     #[error("insufficient sol funds")]
     InsufficientSolFunds,
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
     #[error("io: {0}")]
     IOError(#[from] std::io::Error),
     #[error("keypair not provided")]
     KeyPairNotProvided,
     #[error("key rejected: {0}")]
     KeyRejected(#[from] KeyRejected),
+    #[error("malformed jwk: {0}")]
+    MalformedJwk(String),
     #[error("manifest not found")]
     ManifestNotFound,
     #[error("file path not provided")]
@@ -57,10 +87,18 @@ pub enum Error {
     NoBundleStatusesFound,
     #[error("error getting oracle prices: {0}")]
     OracleGetPriceError(reqwest::Error),
+    #[error("passphrases do not match")]
+    PassphraseMismatch,
+    #[error("keypair file is encrypted, passphrase required")]
+    PassphraseRequired,
     #[error("reqwest: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("ring unspecified: {0}")]
     RingUnspecified(#[from] Unspecified),
+    #[error("rsa: {0}")]
+    Rsa(#[from] rsa::errors::Error),
+    #[error("secp256k1: {0}")]
+    Secp256k1(#[from] libsecp256k1::Error),
     #[error("serde json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("status code not ok")]
@@ -73,10 +111,22 @@ pub enum Error {
     SolanaNetworkError,
     #[error("solana hash parse {0}")]
     TokioJoinError(#[from] tokio::task::JoinError),
+    #[error("transaction id is not the hash of its signature")]
+    TransactionIdMismatch,
+    #[error("keypair encryption {0} not supported")]
+    UnsupportedKeypairEncryption(String),
+    #[error("signature type {0} not supported")]
+    UnsupportedSignatureType(u16),
+    #[error("transaction format {0} not supported")]
+    UnsupportedTransactionFormat(u8),
     #[error("transaction is not signed")]
     UnsignedTransaction,
+    #[error("uploader state not found")]
+    UploaderNotFound,
     #[error("url parse error: {0}")]
     UrlParse(#[from] ParseError),
+    #[error("wrong passphrase for encrypted keypair")]
+    WrongPassphrase,
 }
 
 unsafe impl Send for Error {}
@@ -0,0 +1,209 @@
+//! Failover across multiple gateways, with the health of each tracked during a run.
+
+use crate::{error::Error, rate_limit::RateLimiter};
+use log::debug;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+use url::Url;
+
+/// Number of consecutive failures after which a gateway is only tried once healthy gateways have
+/// failed.
+pub const MAX_GATEWAY_FAILURES: usize = 3;
+
+/// Gateways to fail over to when requests to [`crate::Arweave::base_url`] fail on connection
+/// errors or 5xx responses, with consecutive failures counted for each gateway.
+#[derive(Debug, Default)]
+pub struct Gateways {
+    /// Gateways tried in order after `base_url`.
+    pub fallback_urls: Vec<Url>,
+    failures: Mutex<HashMap<Url, usize>>,
+    next: AtomicUsize,
+}
+
+impl Gateways {
+    pub fn new(fallback_urls: Vec<Url>) -> Self {
+        Self {
+            fallback_urls,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of consecutive failed requests to `url`.
+    pub fn failures(&self, url: &Url) -> usize {
+        *self.failures.lock().unwrap().get(url).unwrap_or(&0)
+    }
+
+    pub fn is_healthy(&self, url: &Url) -> bool {
+        self.failures(url) < MAX_GATEWAY_FAILURES
+    }
+
+    pub fn record_success(&self, url: &Url) {
+        self.failures.lock().unwrap().remove(url);
+    }
+
+    pub fn record_failure(&self, url: &Url) {
+        *self
+            .failures
+            .lock()
+            .unwrap()
+            .entry(url.clone())
+            .or_insert(0) += 1;
+    }
+
+    /// Returns `base_url` followed by the fallback gateways, with healthy gateways ahead of
+    /// unhealthy ones. If `rotate` is true, healthy gateways take turns going first, to spread
+    /// requests across them.
+    pub fn ordered(&self, base_url: &Url, rotate: bool) -> Vec<Url> {
+        let mut urls: Vec<Url> = Vec::with_capacity(self.fallback_urls.len() + 1);
+        for url in std::iter::once(base_url).chain(self.fallback_urls.iter()) {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        let (mut healthy, unhealthy): (Vec<Url>, Vec<Url>) =
+            urls.into_iter().partition(|url| self.is_healthy(url));
+        if rotate && !healthy.is_empty() {
+            let len = healthy.len();
+            healthy.rotate_left(self.next.fetch_add(1, Ordering::Relaxed) % len);
+        }
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    /// Sends the request built by `build` for `path` joined to each gateway in turn, until one
+    /// responds without a connection error or a 5xx status. Each attempt waits on `rate_limiter`
+    /// for a request with a body of `bytes` before it is sent. Returns the last 5xx response if
+    /// every gateway fails, so that callers can handle it as they would for a single gateway.
+    ///
+    /// Only connection errors, timeouts and the 502, 503 and 504 statuses that gateways respond
+    /// with when they are down count as failures of the gateway. Other 5xx statuses can be
+    /// specific to `path`, so they fail over without marking the gateway unhealthy.
+    pub async fn send<F>(
+        &self,
+        base_url: &Url,
+        path: &str,
+        rotate: bool,
+        rate_limiter: &RateLimiter,
+        bytes: usize,
+        build: F,
+    ) -> Result<Response, Error>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let mut last_result = None;
+        for gateway in self.ordered(base_url, rotate) {
+            let url = gateway.join(path)?;
+            rate_limiter.acquire(bytes).await;
+            match build(url).send().await {
+                Ok(resp) if resp.status().is_server_error() => {
+                    debug!("gateway {} responded with {}", gateway, resp.status());
+                    if is_gateway_error(resp.status()) {
+                        self.record_failure(&gateway);
+                    }
+                    last_result = Some(Ok(resp));
+                }
+                Ok(resp) => {
+                    self.record_success(&gateway);
+                    return Ok(resp);
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    debug!("gateway {} failed: {}", gateway, e);
+                    self.record_failure(&gateway);
+                    last_result = Some(Err(e.into()));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        last_result.unwrap_or(Err(Error::StatusCodeNotOk))
+    }
+}
+
+/// Returns true for statuses that mean the gateway itself is down, rather than failing a request.
+fn is_gateway_error(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gateways, MAX_GATEWAY_FAILURES};
+    use crate::{error::Error, rate_limit::RateLimiter, test_utils::serve};
+    use reqwest::{Client, StatusCode};
+    use std::{net::TcpListener, str::FromStr};
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_gateway_failover() -> Result<(), Error> {
+        // Nothing listens on a port once its listener is dropped.
+        let closed_url = {
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            Url::from_str(&format!("http://{}/", listener.local_addr()?)).unwrap()
+        };
+        let (failing_url, failing_server) = serve(vec![
+            ("503 Service Unavailable", String::new()),
+            ("500 Internal Server Error", String::new()),
+        ]);
+        let (ok_url, ok_server) = serve(vec![("200 OK", String::new()), ("200 OK", String::new())]);
+
+        let gateways = Gateways::new(vec![failing_url.clone(), ok_url.clone()]);
+        let client = Client::new();
+        let rate_limiter = RateLimiter::default();
+        let resp = gateways
+            .send(&closed_url, "tx_anchor", false, &rate_limiter, 0, |url| {
+                client.get(url)
+            })
+            .await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(gateways.failures(&closed_url), 1);
+        assert_eq!(gateways.failures(&failing_url), 1);
+        assert_eq!(gateways.failures(&ok_url), 0);
+
+        // Errors for a path fail over without counting against the gateway.
+        let resp = gateways
+            .send(&closed_url, "chunk/0", false, &rate_limiter, 0, |url| {
+                client.get(url)
+            })
+            .await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(gateways.failures(&failing_url), 1);
+        assert_eq!(
+            failing_server
+                .join()
+                .unwrap()
+                .iter()
+                .map(|r| r.target.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/tx_anchor", "/chunk/0"]
+        );
+        assert_eq!(ok_server.join().unwrap()[1].target, "/chunk/0");
+        assert_eq!(gateways.failures(&closed_url), 2);
+
+        for _ in 2..MAX_GATEWAY_FAILURES {
+            gateways.record_failure(&closed_url);
+        }
+        assert!(!gateways.is_healthy(&closed_url));
+        assert_eq!(
+            gateways.ordered(&closed_url, false),
+            vec![failing_url.clone(), ok_url.clone(), closed_url.clone()]
+        );
+        assert_eq!(
+            (0..2)
+                .map(|_| gateways.ordered(&closed_url, true)[0].clone())
+                .collect::<Vec<Url>>(),
+            vec![failing_url.clone(), ok_url]
+        );
+
+        gateways.record_success(&closed_url);
+        assert!(gateways.is_healthy(&closed_url));
+        assert_eq!(gateways.ordered(&closed_url, false)[0], closed_url);
+        Ok(())
+    }
+}
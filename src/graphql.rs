@@ -0,0 +1,506 @@
+//! Typed queries for transactions against the GraphQL endpoint of gateways.
+
+use crate::{
+    error::Error,
+    status::{OutputFormat, QuietDisplay, VerboseDisplay},
+    transaction::{Base64, Tag},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{fmt, str::FromStr};
+use url::Url;
+
+/// Number of transactions requested for each page of results, the most gateways return.
+pub const GRAPHQL_PAGE_SIZE: usize = 100;
+
+/// Query sent to the `graphql` endpoint with the variables from [`TransactionsQuery`].
+pub const TRANSACTIONS_QUERY: &str = r#"query Transactions(
+  $ids: [ID!]
+  $owners: [String!]
+  $tags: [TagFilter!]
+  $block: BlockFilter
+  $first: Int
+  $after: String
+  $sort: SortOrder
+) {
+  transactions(
+    ids: $ids
+    owners: $owners
+    tags: $tags
+    block: $block
+    first: $first
+    after: $after
+    sort: $sort
+  ) {
+    pageInfo {
+      hasNextPage
+    }
+    edges {
+      cursor
+      node {
+        id
+        owner {
+          address
+        }
+        recipient
+        tags {
+          name
+          value
+        }
+        data {
+          size
+          type
+        }
+        fee {
+          winston
+        }
+        quantity {
+          winston
+        }
+        block {
+          height
+          timestamp
+        }
+        bundledIn {
+          id
+        }
+      }
+    }
+  }
+}"#;
+
+/// Order of query results by block height, with pending transactions first for
+/// [`SortOrder::HeightDesc`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SortOrder {
+    HeightDesc,
+    HeightAsc,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder::HeightDesc
+    }
+}
+
+/// Matches transactions with a tag named `name` with any of `values`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagFilter {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// Matches transactions mined in blocks from `min` to `max` height, inclusive.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct BlockFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+}
+
+/// Filters for [`crate::Arweave::query_transactions`]. Transactions have to match every filter
+/// that is set, and any of the values within each filter.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct TransactionsQuery {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<Base64>,
+    /// Wallet addresses of owners.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<Base64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<TagFilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockFilter>,
+    pub sort: SortOrder,
+}
+
+impl TransactionsQuery {
+    pub fn id(mut self, id: Base64) -> Self {
+        self.ids.push(id);
+        self
+    }
+
+    pub fn owner(mut self, owner: Base64) -> Self {
+        self.owners.push(owner);
+        self
+    }
+
+    /// Adds `value` to the values matched for tag `name`.
+    pub fn tag(mut self, name: &str, value: &str) -> Self {
+        match self.tags.iter_mut().find(|t| t.name == name) {
+            Some(filter) => filter.values.push(value.to_string()),
+            None => self.tags.push(TagFilter {
+                name: name.to_string(),
+                values: vec![value.to_string()],
+            }),
+        }
+        self
+    }
+
+    /// Restricts results to blocks from `min` to `max` height, either of which can be open.
+    pub fn block_range(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.block = Some(BlockFilter { min, max });
+        self
+    }
+
+    pub fn sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Returns the variables for [`TRANSACTIONS_QUERY`] to get the page of results following
+    /// cursor `after`, or the first page if `None`.
+    pub fn variables(&self, after: Option<&str>) -> Value {
+        let mut variables = serde_json::to_value(self).unwrap();
+        variables["first"] = json!(GRAPHQL_PAGE_SIZE);
+        if let Some(after) = after {
+            variables["after"] = json!(after);
+        }
+        variables
+    }
+}
+
+/// Transaction returned by a query, with amounts in winstons.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct QueriedTransaction {
+    pub id: Base64,
+    /// Wallet address of the owner.
+    pub owner: Base64,
+    pub target: Option<Base64>,
+    pub quantity: u64,
+    pub reward: u64,
+    pub data_size: u64,
+    pub content_type: Option<String>,
+    pub tags: Vec<Tag<String>>,
+    /// Not set for pending transactions.
+    pub block_height: Option<u64>,
+    pub block_timestamp: Option<u64>,
+    /// Id of the bundle transaction, for data items.
+    pub bundled_in: Option<Base64>,
+}
+
+impl QueriedTransaction {
+    pub fn header_string(output_format: &OutputFormat) -> String {
+        match output_format {
+            OutputFormat::Display => {
+                format!(
+                    " {:<43}  {:<43}  {:>8}  {:>12}  {}\n{:-<136}",
+                    "id", "owner", "block", "bytes", "content type", ""
+                )
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+impl QuietDisplay for QueriedTransaction {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "{}", self.id)
+    }
+}
+
+impl fmt::Display for QueriedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let block = self
+            .block_height
+            .map_or(String::from("pending"), |h| h.to_string());
+        writeln!(
+            f,
+            " {:<43}  {:<43}  {:>8}  {:>12}  {}",
+            self.id,
+            self.owner,
+            block,
+            self.data_size,
+            self.content_type.as_deref().unwrap_or(""),
+        )
+    }
+}
+
+impl VerboseDisplay for QueriedTransaction {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "{:<16} {}", "id:", self.id)?;
+        writeln!(w, "{:<16} {}", "owner:", self.owner)?;
+        if let Some(target) = &self.target {
+            writeln!(w, "{:<16} {}", "target:", target)?;
+        }
+        writeln!(w, "{:<16} {}", "quantity:", self.quantity)?;
+        writeln!(w, "{:<16} {}", "reward:", self.reward)?;
+        writeln!(w, "{:<16} {}", "data_size:", self.data_size)?;
+        if let Some(content_type) = &self.content_type {
+            writeln!(w, "{:<16} {}", "content_type:", content_type)?;
+        }
+        for tag in &self.tags {
+            writeln!(w, "{:<16} {}: {}", "tag:", tag.name, tag.value)?;
+        }
+        match self.block_height {
+            Some(height) => writeln!(w, "{:<16} {}", "block_height:", height)?,
+            None => writeln!(w, "{:<16} pending", "block_height:")?,
+        }
+        if let Some(timestamp) = self.block_timestamp {
+            writeln!(w, "{:<16} {}", "block_timestamp:", timestamp)?;
+        }
+        if let Some(bundled_in) = &self.bundled_in {
+            writeln!(w, "{:<16} {}", "bundled_in:", bundled_in)?;
+        }
+        writeln!(w)
+    }
+}
+
+/// Page of results for a [`TransactionsQuery`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransactionsPage {
+    pub transactions: Vec<QueriedTransaction>,
+    /// Cursor of the last transaction, to get the next page after.
+    pub cursor: Option<String>,
+    pub has_next_page: bool,
+    /// Gateway that responded with the page, which issued its cursor.
+    pub gateway: Option<Url>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlResponse {
+    data: Option<GraphqlData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GraphqlData {
+    transactions: Connection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection {
+    page_info: PageInfo,
+    edges: Vec<Edge>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+}
+
+#[derive(Deserialize)]
+struct Edge {
+    cursor: String,
+    node: Node,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Node {
+    id: Base64,
+    owner: Owner,
+    recipient: String,
+    tags: Vec<Tag<String>>,
+    data: NodeData,
+    fee: Amount,
+    quantity: Amount,
+    block: Option<Block>,
+    bundled_in: Option<BundledIn>,
+}
+
+#[derive(Deserialize)]
+struct Owner {
+    address: Base64,
+}
+
+#[derive(Deserialize)]
+struct NodeData {
+    size: String,
+    #[serde(rename = "type")]
+    content_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Amount {
+    winston: String,
+}
+
+#[derive(Deserialize)]
+struct Block {
+    height: u64,
+    timestamp: u64,
+}
+
+#[derive(Deserialize)]
+struct BundledIn {
+    id: Base64,
+}
+
+fn parse_number(number: &str) -> Result<u64, Error> {
+    u64::from_str(number).map_err(|_| Error::Graphql(format!("invalid number {}", number)))
+}
+
+impl TryFrom<Node> for QueriedTransaction {
+    type Error = Error;
+
+    fn try_from(node: Node) -> Result<Self, Error> {
+        let target = match node.recipient.as_str() {
+            "" => None,
+            recipient => Some(Base64::from_str(recipient)?),
+        };
+        Ok(QueriedTransaction {
+            id: node.id,
+            owner: node.owner.address,
+            target,
+            quantity: parse_number(&node.quantity.winston)?,
+            reward: parse_number(&node.fee.winston)?,
+            data_size: parse_number(&node.data.size)?,
+            content_type: node.data.content_type,
+            tags: node.tags,
+            block_height: node.block.as_ref().map(|b| b.height),
+            block_timestamp: node.block.as_ref().map(|b| b.timestamp),
+            bundled_in: node.bundled_in.map(|b| b.id),
+        })
+    }
+}
+
+/// Parses the response body of a [`TRANSACTIONS_QUERY`], failing with the messages of any
+/// errors reported by the gateway.
+pub fn parse_transactions_page(body: &[u8]) -> Result<TransactionsPage, Error> {
+    let resp: GraphqlResponse = serde_json::from_slice(body)?;
+    let connection = match (resp.data, resp.errors) {
+        (_, Some(errors)) if !errors.is_empty() => {
+            let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+            return Err(Error::Graphql(messages.join(", ")));
+        }
+        (Some(data), _) => data.transactions,
+        (None, _) => return Err(Error::Graphql(String::from("no data in response"))),
+    };
+
+    let cursor = connection.edges.last().map(|e| e.cursor.clone());
+    let transactions = connection
+        .edges
+        .into_iter()
+        .map(|e| QueriedTransaction::try_from(e.node))
+        .collect::<Result<Vec<QueriedTransaction>, Error>>()?;
+    Ok(TransactionsPage {
+        transactions,
+        cursor,
+        has_next_page: connection.page_info.has_next_page,
+        gateway: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SortOrder, TransactionsQuery, GRAPHQL_PAGE_SIZE};
+    use crate::{
+        error::Error, query_transactions_stream, test_utils::serve, transaction::Base64, Arweave,
+    };
+    use futures::TryStreamExt;
+    use matches::assert_matches;
+    use serde_json::{json, Value};
+    use std::str::FromStr;
+
+    const OWNER: &str = "7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg";
+
+    fn edge(id: &str, cursor: &str) -> Value {
+        json!({
+            "cursor": cursor,
+            "node": {
+                "id": id,
+                "owner": { "address": OWNER },
+                "recipient": "",
+                "tags": [{ "name": "Content-Type", "value": "image/png" }],
+                "data": { "size": "1024", "type": "image/png" },
+                "fee": { "winston": "53408197" },
+                "quantity": { "winston": "0" },
+                "block": { "height": 930000, "timestamp": 1652000000 },
+                "bundledIn": null
+            }
+        })
+    }
+
+    #[test]
+    fn test_transactions_query() {
+        let query = TransactionsQuery::default()
+            .owner(Base64::from_str(OWNER).unwrap())
+            .tag("Content-Type", "image/png")
+            .tag("App-Name", "arloader")
+            .tag("Content-Type", "image/jpeg")
+            .block_range(Some(930000), None)
+            .sort(SortOrder::HeightAsc);
+        assert_eq!(
+            query.variables(Some("cursor")),
+            json!({
+                "owners": [OWNER],
+                "tags": [
+                    { "name": "Content-Type", "values": ["image/png", "image/jpeg"] },
+                    { "name": "App-Name", "values": ["arloader"] }
+                ],
+                "block": { "min": 930000 },
+                "sort": "HEIGHT_ASC",
+                "first": GRAPHQL_PAGE_SIZE,
+                "after": "cursor"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_transactions_stream() -> Result<(), Error> {
+        let ids = [
+            "ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc",
+            "I-D4AkMq4rs4Sut0BHSHNCL0jS9vA8vXbi6jwzhjfv4",
+            "k5SQMAVPxhS-GAsbZbbTV9469qZj7oH-_SM3H45nTk8",
+        ];
+        let pages = vec![
+            json!({ "data": { "transactions": {
+                "pageInfo": { "hasNextPage": true },
+                "edges": [edge(ids[0], "c0"), edge(ids[1], "c1")]
+            }}}),
+            json!({ "data": { "transactions": {
+                "pageInfo": { "hasNextPage": false },
+                "edges": [edge(ids[2], "c2")]
+            }}}),
+        ];
+
+        // Stand-in gateway that responds with `pages` in turn.
+        let (url, server) = serve(pages.iter().map(|p| ("200 OK", p.to_string())).collect());
+
+        let arweave = Arweave {
+            base_url: url,
+            ..Arweave::default()
+        };
+        let query = TransactionsQuery::default().owner(Base64::from_str(OWNER).unwrap());
+        let transactions: Vec<_> = query_transactions_stream(&arweave, query)
+            .try_collect()
+            .await?;
+        let variables = server
+            .join()
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).map(|b| b["variables"].clone()))
+            .collect::<Result<Vec<Value>, _>>()?;
+
+        assert_eq!(
+            transactions
+                .iter()
+                .map(|t| t.id.to_string())
+                .collect::<Vec<String>>(),
+            ids
+        );
+        assert_eq!(transactions[0].data_size, 1024);
+        assert_eq!(transactions[0].reward, 53408197);
+        assert_eq!(transactions[0].target, None);
+        assert_eq!(transactions[0].block_height, Some(930000));
+        assert_eq!(variables[0].get("after"), None);
+        assert_eq!(variables[1]["after"], "c1");
+        assert_eq!(variables[1]["owners"], json!([OWNER]));
+
+        assert_matches!(
+            super::parse_transactions_page(br#"{"errors":[{"message":"bad query"}]}"#),
+            Err(Error::Graphql(message)) if message == "bad query"
+        );
+        Ok(())
+    }
+}
@@ -73,7 +73,7 @@
 use chrono::Utc;
 use futures::{
     future::{try_join, try_join_all},
-    stream, Stream, StreamExt,
+    stream, Stream, StreamExt, TryStreamExt,
 };
 use glob::glob;
 use log::debug;
@@ -82,7 +82,7 @@ use rayon::prelude::*;
 use reqwest::{
     self,
     header::{ACCEPT, CONTENT_TYPE},
-    Client, StatusCode as ResponseStatusCode,
+    Client, RequestBuilder, Response, StatusCode as ResponseStatusCode,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -90,33 +90,61 @@ use solana_sdk::signer::keypair::Keypair;
 use std::{
     collections::HashMap,
     fmt::Write,
+    io::{Cursor, Read, Seek},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
 use tokio::{
     fs,
-    time::{sleep, Duration},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    time::{Duration, Instant},
 };
 use url::Url;
 
 pub mod api;
 pub mod bundle;
+pub mod client;
 pub mod commands;
 pub mod crypto;
 pub mod error;
+pub mod gateway;
+pub mod graphql;
 pub mod merkle;
+pub mod rate_limit;
 pub mod raw;
+pub mod retry;
+pub mod signer;
 pub mod solana;
 pub mod status;
+#[cfg(test)]
+mod test_utils;
 pub mod transaction;
 pub mod utils;
 
-use bundle::DataItem;
+use bundle::{
+    split_bundle, BundleEntry, BundleReader, BundleWriter, DataItem, DataItemOptions,
+    DataItemReport, MAX_BUNDLE_DEPTH,
+};
+use client::ClientOptions;
 use error::Error;
-use merkle::{generate_data_root, generate_leaves, resolve_proofs};
+use gateway::Gateways;
+use graphql::{
+    parse_transactions_page, QueriedTransaction, TransactionsPage, TransactionsQuery,
+    TRANSACTIONS_QUERY,
+};
+use merkle::{
+    generate_data_root, generate_leaf, generate_leaves, generate_leaves_from_reader,
+    resolve_proofs, validate_chunk, Proof, HASH_SIZE,
+};
+use rate_limit::RateLimiter;
+use retry::RetryPolicy;
+use signer::{verify_signature, Signer, SIGNATURE_TYPE_ARWEAVE};
 use solana::{create_sol_transaction, get_sol_ar_signature, SigResponse, FLOOR, RATE};
-use status::{BundleStatus, Filterable, Status, StatusCode};
-use transaction::{Base64, Chunk, FromUtf8Strs, Tag, ToItems, Transaction};
+use status::{BundleStatus, BundlerReceipt, Filterable, Status, StatusCode};
+use transaction::{
+    Base64, Chunk, FromUtf8Strs, RawChunk, Tag, ToItems, Transaction, TransactionOffset, Uploader,
+};
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -134,12 +162,20 @@ pub const MAX_TX_DATA: u64 = 10_000_000;
 /// of simultaneous request to the `chunk/ endpoint`.
 pub const CHUNKS_BUFFER_FACTOR: usize = 20;
 
-/// Number of times to retry posting chunks if not successful.
+/// Default number of times to retry failed requests, used by [`RetryPolicy`].
 pub const CHUNKS_RETRIES: u16 = 10;
 
-/// Number of seconds to wait between retying to post a failed chunk.
+/// Default number of seconds to wait before the first retry of a failed request, used by
+/// [`RetryPolicy`].
 pub const CHUNKS_RETRY_SLEEP: u64 = 1;
 
+/// Minimum number of seconds between writes of [`Uploader`] progress while posting chunks.
+pub const UPLOADER_WRITE_INTERVAL: u64 = 5;
+
+/// Name of the tag added to uploads when [`Arweave::dedupe`] is set, with the hash of the file
+/// returned by [`file_hash`] as its value.
+pub const FILE_HASH_TAG: &str = "File-Hash";
+
 //=========================
 // Streams
 //=========================
@@ -173,6 +209,39 @@ pub fn upload_bundles_stream<'a>(
         .buffer_unordered(bundles_buffer)
 }
 
+/// Returns a stream of all transactions matching `query`, getting pages of results from
+/// [`Arweave::query_transactions`] as the stream is read.
+pub fn query_transactions_stream<'a>(
+    arweave: &'a Arweave,
+    query: TransactionsQuery,
+) -> impl Stream<Item = Result<QueriedTransaction, Error>> + 'a {
+    // The state is the cursor to get the next page after and the gateway that issued it, or
+    // `None` once there are no more.
+    stream::try_unfold(Some(None), move |after: Option<Option<(String, Url)>>| {
+        let query = query.clone();
+        async move {
+            let page = match after {
+                Some(Some((cursor, gateway))) => {
+                    arweave
+                        .query_transactions_from(&query, &cursor, &gateway)
+                        .await?
+                }
+                Some(None) => arweave.query_transactions(&query, None).await?,
+                None => return Ok::<_, Error>(None),
+            };
+            let next = match (page.cursor, page.gateway) {
+                (Some(cursor), Some(gateway)) if page.has_next_page => {
+                    Some(Some((cursor, gateway)))
+                }
+                _ => None,
+            };
+            let transactions = stream::iter(page.transactions.into_iter().map(Ok));
+            Ok(Some((transactions, next)))
+        }
+    })
+    .try_flatten()
+}
+
 /// Queries network and updates locally stored [`BundleStatus`] structs.
 pub fn update_bundle_statuses_stream<'a, IP>(
     arweave: &'a Arweave,
@@ -226,11 +295,37 @@ pub fn upload_transaction_chunks_stream<'a>(
     signed_transaction: Transaction,
     buffer: usize,
 ) -> impl Stream<Item = Result<usize, Error>> + 'a {
-    let client = Client::new();
     stream::iter(0..signed_transaction.chunks.len())
         .map(move |i| {
             let chunk = signed_transaction.get_chunk(i).unwrap();
-            arweave.post_chunk_with_retries(chunk, client.clone())
+            arweave.post_chunk_with_retries(chunk, arweave.client.clone())
+        })
+        .buffer_unordered(buffer)
+}
+
+/// Uploads a stream of chunks, reading the data for each chunk from `file_path` as it gets
+/// posted, for transactions created with [`Arweave::merklize_reader`]. Only chunks with indices
+/// in `chunk_indices` are posted and each result is returned along with its chunk index.
+pub fn upload_transaction_chunks_from_file_stream<'a>(
+    arweave: &'a Arweave,
+    signed_transaction: Transaction,
+    file_path: PathBuf,
+    chunk_indices: Vec<usize>,
+    buffer: usize,
+) -> impl Stream<Item = (usize, Result<usize, Error>)> + 'a {
+    let signed_transaction = Arc::new(signed_transaction);
+    stream::iter(chunk_indices)
+        .map(move |i| {
+            let signed_transaction = signed_transaction.clone();
+            let file_path = file_path.clone();
+            let client = arweave.client.clone();
+            async move {
+                let result = match signed_transaction.get_chunk_from_file(i, &file_path).await {
+                    Ok(chunk) => arweave.post_chunk_with_retries(chunk, client).await,
+                    Err(e) => Err(e),
+                };
+                (i, result)
+            }
         })
         .buffer_unordered(buffer)
 }
@@ -293,6 +388,24 @@ where
         .buffer_unordered(buffer)
 }
 
+/// Uploads files matching glob pattern to a bundler as individual data items, returning a stream
+/// of [`Status`] structs.
+pub fn upload_files_to_bundler_stream<'a, IP>(
+    arweave: &'a Arweave,
+    paths_iter: IP,
+    tags: Vec<Tag<String>>,
+    log_dir: Option<PathBuf>,
+    bundler_url: &'a Url,
+    buffer: usize,
+) -> impl Stream<Item = Result<Status, Error>> + 'a
+where
+    IP: Iterator<Item = PathBuf> + Send + Sync + 'a,
+{
+    stream::iter(paths_iter)
+        .map(move |p| arweave.upload_file_to_bundler(p, tags.clone(), bundler_url, log_dir.clone()))
+        .buffer_unordered(buffer)
+}
+
 /// Queries network and updates locally stored [`Status`] structs.
 pub fn update_statuses_stream<'a, IP>(
     arweave: &'a Arweave,
@@ -323,6 +436,66 @@ pub struct OraclePricePair {
     pub usd: f32,
 }
 
+/// Returns a [`Status`] for a file found already uploaded as `uploaded`, recording its id
+/// instead of uploading the file again.
+pub fn uploaded_file_status(file_path: PathBuf, uploaded: QueriedTransaction) -> Status {
+    let content_type = uploaded
+        .content_type
+        .unwrap_or_else(|| mime_guess::mime::OCTET_STREAM.to_string());
+    Status {
+        id: uploaded.id,
+        status: if uploaded.block_height.is_some() {
+            StatusCode::Confirmed
+        } else {
+            StatusCode::Pending
+        },
+        file_path: Some(file_path),
+        content_type,
+        ..Status::default()
+    }
+}
+
+/// Returns the value of the [`FILE_HASH_TAG`] for `data`, its BLAKE3 hash, like `blake3:<hex>`.
+pub fn data_hash(data: &[u8]) -> String {
+    format!("blake3:{}", blake3::hash(data))
+}
+
+/// Returns `tags` with a [`FILE_HASH_TAG`] for `hash` added, if there is one.
+fn with_file_hash_tag(
+    mut tags: Vec<Tag<String>>,
+    hash: Option<&String>,
+) -> Result<Vec<Tag<String>>, Error> {
+    if let Some(hash) = hash {
+        tags.push(Tag::<String>::from_utf8_strs(FILE_HASH_TAG, hash)?);
+    }
+    Ok(tags)
+}
+
+/// Same as [`data_hash`] for the file at `file_path`, reading it a block at a time.
+pub async fn file_hash(file_path: &Path) -> Result<String, Error> {
+    let mut file = fs::File::open(file_path).await?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buf = vec![0; BLOCK_SIZE as usize];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("blake3:{}", hasher.finalize()))
+}
+
+/// Gets content type from [magic numbers](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types),
+/// defaulting to `application/octet-stream`.
+pub fn infer_content_type(data: &[u8]) -> &'static str {
+    if let Some(kind) = infer::get(data) {
+        kind.mime_type()
+    } else {
+        "application/octet-stream"
+    }
+}
+
 /// Tuple struct includes two elements: chunk of paths and aggregatge data size of paths.
 #[derive(Clone, Debug)]
 pub struct PathsChunk(Vec<PathBuf>, u64);
@@ -338,6 +511,40 @@ pub fn file_stem_is_valid_txid(file_path: &PathBuf) -> bool {
     }
 }
 
+/// Converts an amount of AR given as a decimal string, like `1.5`, to winstons without going
+/// through floating point.
+pub fn ar_to_winstons(ar: &str) -> Result<u64, Error> {
+    let invalid = || Error::InvalidArAmount(ar.to_string());
+    let (whole, fraction) = ar.split_once('.').unwrap_or((ar, ""));
+    // WINSTONS_PER_AR is 10^12.
+    let decimals = 12;
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > decimals
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u64>().map_err(|_| invalid())?
+    };
+    let fraction = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<width$}", fraction, width = decimals)
+            .parse::<u64>()
+            .map_err(|_| invalid())?
+    };
+    whole
+        .checked_mul(WINSTONS_PER_AR)
+        .and_then(|w| w.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
 //=========================
 // Arweave
 //=========================
@@ -364,7 +571,30 @@ pub struct Arweave {
     pub name: String,
     pub units: String,
     pub base_url: Url,
+    /// Gateways to fail over to when requests to `base_url` fail.
+    pub gateways: Gateways,
+    /// Client used for every request, built from `client_options`.
+    pub client: Client,
+    /// Options `client` was built with, for the headers and timeout of requests to gateways and
+    /// bundlers.
+    pub client_options: ClientOptions,
+    /// Retries for posting chunks and transactions, getting statuses and SOL signatures.
+    pub retry_policy: RetryPolicy,
+    /// Limits requests to gateways and bundlers, shared by all uploads.
+    pub rate_limiter: RateLimiter,
     pub crypto: crypto::Provider,
+    /// Signs data items and transactions in place of `crypto` if provided.
+    pub signer: Option<Box<dyn Signer>>,
+    /// Signs data items in place of `signer` or `crypto` if provided, for keys, like Solana and
+    /// Ethereum keys, that can't sign transactions.
+    pub data_item_signer: Option<Box<dyn Signer>>,
+    /// Target and anchor for data items.
+    pub data_item_options: DataItemOptions,
+    /// Tags uploaded files with their [`FILE_HASH_TAG`] and, instead of uploading files again,
+    /// reuses the ids of files with the same hash already uploaded from the same wallet. Files
+    /// uploaded as individual transactions paid for with SOL are tagged, but not looked up, since
+    /// their transactions are signed by the wallet of the SOL service.
+    pub dedupe: bool,
 }
 
 impl Default for Arweave {
@@ -373,7 +603,16 @@ impl Default for Arweave {
             name: String::from("arweave"),
             units: String::from("winstons"),
             base_url: Url::from_str("https://arweave.net/").unwrap(),
+            gateways: Gateways::default(),
+            client: ClientOptions::default().build().unwrap(),
+            client_options: ClientOptions::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
             crypto: crypto::Provider::default(),
+            signer: None,
+            data_item_signer: None,
+            data_item_options: DataItemOptions::default(),
+            dedupe: false,
         }
     }
 }
@@ -390,6 +629,24 @@ impl Arweave {
         Ok(arweave)
     }
 
+    /// Creates an [`Arweave`] from a keypair file encrypted with [`crypto::EncryptedKeypair`],
+    /// decrypting it with `passphrase`.
+    pub async fn from_encrypted_keypair_path(
+        keypair_path: PathBuf,
+        passphrase: &str,
+        base_url: Url,
+    ) -> Result<Arweave, Error> {
+        let crypto =
+            crypto::Provider::from_encrypted_keypair_path(keypair_path, passphrase).await?;
+        let arweave = Arweave {
+            base_url,
+            crypto,
+            ..Default::default()
+        };
+
+        Ok(arweave)
+    }
+
     pub fn from_keypair_path_sync(keypair_path: PathBuf, base_url: Url) -> Result<Arweave, Error> {
         let crypto = crypto::Provider::from_keypair_path_sync(keypair_path)?;
         let arweave = Arweave {
@@ -401,30 +658,242 @@ impl Arweave {
         Ok(arweave)
     }
 
+    /// Creates an [`Arweave`] that signs with `signer` instead of a keypair file.
+    pub fn from_signer(signer: Box<dyn Signer>, base_url: Url) -> Arweave {
+        Arweave {
+            base_url,
+            signer: Some(signer),
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the client used for requests with one built from `options`.
+    pub fn with_client_options(self, options: &ClientOptions) -> Result<Arweave, Error> {
+        Ok(Arweave {
+            client: options.build()?,
+            client_options: options.clone(),
+            ..self
+        })
+    }
+
+    /// Returns the [`Signer`] used for data items, `data_item_signer` or `signer` if provided, or
+    /// otherwise `crypto`.
+    pub fn signer(&self) -> &dyn Signer {
+        match (&self.data_item_signer, &self.signer) {
+            (Some(signer), _) | (None, Some(signer)) => signer.as_ref(),
+            (None, None) => &self.crypto,
+        }
+    }
+
+    /// Returns the [`Signer`] used for transactions, `signer` if provided, or otherwise `crypto`.
+    ///
+    /// Transactions can only be signed with Arweave keys, so returns an error if `signer` has a
+    /// different signature type.
+    pub fn transaction_signer(&self) -> Result<&dyn Signer, Error> {
+        match &self.signer {
+            Some(signer) if signer.signature_type() != SIGNATURE_TYPE_ARWEAVE => {
+                Err(Error::UnsupportedSignatureType(signer.signature_type()))
+            }
+            Some(signer) => Ok(signer.as_ref()),
+            None => Ok(&self.crypto),
+        }
+    }
+
     //-------------------------
     // Get Request
     //-------------------------
 
+    /// Sends the request built by `build` for `path` on `base_url`, failing over to `gateways`
+    /// on connection errors or 5xx responses.
+    async fn send_to_gateways<F>(
+        &self,
+        path: &str,
+        bytes: usize,
+        build: F,
+    ) -> Result<Response, Error>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        self.gateways
+            .send(
+                &self.base_url,
+                path,
+                false,
+                &self.rate_limiter,
+                bytes,
+                |url| build(url).headers(self.client_options.headers.clone()),
+            )
+            .await
+    }
+
+    /// Sends a GET request for `path` with [`Arweave::send_to_gateways`].
+    async fn get_from_gateways(&self, path: &str) -> Result<Response, Error> {
+        self.send_to_gateways(path, 0, |url| self.client.get(url))
+            .await
+    }
+
+    /// Gets a page of transactions matching `query` from the `graphql` endpoint, following the
+    /// transaction with cursor `after`, or the first page if `None`.
+    ///
+    /// Cursors are only valid on the gateway that issued them, so pages after the first should be
+    /// requested from [`TransactionsPage::gateway`] with [`Arweave::query_transactions_from`]
+    /// rather than failing over to another gateway.
+    pub async fn query_transactions(
+        &self,
+        query: &TransactionsQuery,
+        after: Option<&str>,
+    ) -> Result<TransactionsPage, Error> {
+        self.post_transactions_query(query, after, None).await
+    }
+
+    /// Same as [`Arweave::query_transactions`], but only from `gateway`, which issued `after`.
+    pub async fn query_transactions_from(
+        &self,
+        query: &TransactionsQuery,
+        after: &str,
+        gateway: &Url,
+    ) -> Result<TransactionsPage, Error> {
+        self.post_transactions_query(query, Some(after), Some(gateway))
+            .await
+    }
+
+    /// Posts `query` to `gateway`, or to `base_url` failing over to `gateways` if `None`,
+    /// recording the gateway that responded on the page.
+    async fn post_transactions_query(
+        &self,
+        query: &TransactionsQuery,
+        after: Option<&str>,
+        gateway: Option<&Url>,
+    ) -> Result<TransactionsPage, Error> {
+        let body = serde_json::to_vec(&json!({
+            "query": TRANSACTIONS_QUERY,
+            "variables": query.variables(after),
+        }))?;
+        let request = |url| {
+            self.client
+                .post(url)
+                .body(body.clone())
+                .header(&ACCEPT, "application/json")
+                .header(&CONTENT_TYPE, "application/json")
+        };
+        self.retry_policy
+            .retry(|| async {
+                let resp = match gateway {
+                    Some(gateway) => {
+                        self.rate_limiter.acquire(body.len()).await;
+                        request(gateway.join("graphql")?)
+                            .headers(self.client_options.headers.clone())
+                            .send()
+                            .await?
+                    }
+                    None => {
+                        self.send_to_gateways("graphql", body.len(), request)
+                            .await?
+                    }
+                };
+                let gateway = resp.url().join("./")?;
+                match resp.status() {
+                    ResponseStatusCode::OK => Ok(TransactionsPage {
+                        gateway: Some(gateway),
+                        ..parse_transactions_page(&resp.bytes().await?)?
+                    }),
+                    status => Err(Error::ArweaveNetworkError(status)),
+                }
+            })
+            .await
+    }
+
+    /// Gets the latest transaction or data item from the wallet at `owner` tagged with
+    /// [`FILE_HASH_TAG`] `hash`, if there is one.
+    pub async fn find_file_by_hash(
+        &self,
+        hash: &str,
+        owner: Base64,
+    ) -> Result<Option<QueriedTransaction>, Error> {
+        let query = TransactionsQuery::default()
+            .owner(owner)
+            .tag(FILE_HASH_TAG, hash);
+        let page = self.query_transactions(&query, None).await?;
+        Ok(page.transactions.into_iter().next())
+    }
+
+    /// Hashes the file at `file_path` and looks it up with [`Arweave::find_file_by_hash`] for the
+    /// wallet of `signer`, returning the hash and the existing upload, if found.
+    async fn find_uploaded_file(
+        &self,
+        file_path: &Path,
+        signer: &dyn Signer,
+    ) -> Result<(String, Option<QueriedTransaction>), Error> {
+        let hash = file_hash(file_path).await?;
+        let uploaded = self
+            .find_file_by_hash(&hash, signer.wallet_address()?)
+            .await?;
+        Ok((hash, uploaded))
+    }
+
+    /// With `dedupe` set, splits the files in `paths_chunk` already uploaded from the wallet of
+    /// [`Arweave::signer`] off from the rest, returning the rest with their hashes and
+    /// [`Status`]es with the ids of the uploaded files.
+    async fn split_uploaded_files(
+        &self,
+        paths_chunk: PathsChunk,
+    ) -> Result<(PathsChunk, HashMap<PathBuf, String>, Vec<Status>), Error> {
+        if !self.dedupe {
+            return Ok((paths_chunk, HashMap::new(), Vec::new()));
+        }
+        let signer = self.signer();
+        let found = try_join_all(
+            paths_chunk
+                .0
+                .iter()
+                .map(|p| self.find_uploaded_file(p, signer)),
+        )
+        .await?;
+
+        let PathsChunk(paths, mut data_size) = paths_chunk;
+        let mut remaining = Vec::new();
+        let mut hashes = HashMap::new();
+        let mut statuses = Vec::new();
+        for (path, (hash, uploaded)) in paths.into_iter().zip(found) {
+            match uploaded {
+                Some(uploaded) => {
+                    data_size = data_size.saturating_sub(uploaded.data_size);
+                    statuses.push(uploaded_file_status(path, uploaded));
+                }
+                None => {
+                    hashes.insert(path.clone(), hash);
+                    remaining.push(path);
+                }
+            }
+        }
+        Ok((PathsChunk(remaining, data_size), hashes, statuses))
+    }
+
     /// Get pending network transaction count.
     pub async fn get_pending_count(&self) -> Result<usize, Error> {
-        let url = self.base_url.join("tx/pending")?;
-        let tx_ids: Vec<String> = reqwest::get(url).await?.json().await?;
+        let tx_ids: Vec<String> = self.get_from_gateways("tx/pending").await?.json().await?;
         Ok(tx_ids.len())
     }
 
     /// Returns price of uploading data to the network in winstons and USD per AR and USD per SOL
     /// as a BigUint with two decimals.
     pub async fn get_price(&self, bytes: &u64) -> Result<BytesPrice, Error> {
-        let url = self.base_url.join("price/")?.join(&bytes.to_string())?;
-        let winstons = reqwest::get(url)
+        let winstons = self
+            .get_from_gateways(&format!("price/{}", bytes))
             .await
-            .map_err(|e| Error::ArweaveGetPriceError(e))?
+            .map_err(|e| match e {
+                Error::Reqwest(e) => Error::ArweaveGetPriceError(e),
+                e => e,
+            })?
             .json::<u64>()
             .await?;
         let oracle_url =
             "https://api.coingecko.com/api/v3/simple/price?ids=arweave,solana&vs_currencies=usd";
 
-        let oracle_price = reqwest::get(oracle_url)
+        let oracle_price = self
+            .client
+            .get(oracle_url)
+            .send()
             .await
             .map_err(|e| Error::OracleGetPriceError(e))?
             .json::<OraclePrice>()
@@ -449,11 +918,111 @@ impl Arweave {
         Ok((base, incremental))
     }
 
-    /// Gets transaction from the network.
+    /// Gets transaction from the network, fetching the inline data of format 1 transactions if it
+    /// isn't included.
     pub async fn get_transaction(&self, id: &Base64) -> Result<Transaction, Error> {
-        let url = self.base_url.join("tx/")?.join(&id.to_string())?;
-        let resp = reqwest::get(url).await?.json::<Transaction>().await?;
-        Ok(resp)
+        let mut transaction = self
+            .get_from_gateways(&format!("tx/{}", id))
+            .await?
+            .json::<Transaction>()
+            .await?;
+
+        // Inline data is signed in format 1 transactions, so fetch it if it was left out.
+        if transaction.format == 1 && transaction.data.0.is_empty() && transaction.data_size > 0 {
+            let data = self
+                .get_from_gateways(&format!("tx/{}/data", id))
+                .await?
+                .text()
+                .await?;
+            transaction.data = Base64::from_str(&data)?;
+        }
+        Ok(transaction)
+    }
+
+    /// Gets the size and absolute end offset in the weave of a transaction's data.
+    pub async fn get_transaction_offset(&self, id: &Base64) -> Result<TransactionOffset, Error> {
+        let resp = self.get_from_gateways(&format!("tx/{}/offset", id)).await?;
+        match resp.status() {
+            ResponseStatusCode::OK => Ok(resp.json::<TransactionOffset>().await?),
+            status => Err(Error::ArweaveNetworkError(status)),
+        }
+    }
+
+    /// Gets the chunk that includes the absolute weave `offset`.
+    pub async fn get_raw_chunk(&self, offset: usize) -> Result<RawChunk, Error> {
+        let resp = self.get_from_gateways(&format!("chunk/{}", offset)).await?;
+        match resp.status() {
+            ResponseStatusCode::OK => Ok(resp.json::<RawChunk>().await?),
+            status => Err(Error::ArweaveNetworkError(status)),
+        }
+    }
+
+    /// Downloads the data of a transaction chunk by chunk, validating each chunk against the
+    /// transaction's `data_root` before writing it to `writer`. Inline data of format 1
+    /// transactions is checked against `data_size` and the transaction's signature instead.
+    /// Returns the number of bytes written.
+    pub async fn download_transaction_data<W>(
+        &self,
+        id: &Base64,
+        writer: &mut W,
+    ) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let transaction = self.get_transaction(id).await?;
+
+        // Format 1 transactions without a data root include their data in the transaction,
+        // where it is covered by the signature.
+        if transaction.data_root.0.is_empty() {
+            if transaction.data.0.len() as u64 != transaction.data_size {
+                return Err(Error::DataSizeMismatch);
+            }
+            transaction.verify()?;
+            writer.write_all(&transaction.data.0).await?;
+            writer.flush().await?;
+            return Ok(transaction.data.0.len() as u64);
+        }
+
+        let root_id: [u8; HASH_SIZE] = transaction
+            .data_root
+            .0
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InvalidProof)?;
+        let TransactionOffset { size, offset } = self.get_transaction_offset(id).await?;
+        let start = offset + 1 - size;
+
+        let mut written = 0;
+        while written < size {
+            let RawChunk {
+                data_path, chunk, ..
+            } = self.get_raw_chunk(start + written).await?;
+
+            // The note at the end of the data path is the end of the chunk's byte range, which
+            // has to pick up where the previous chunk left off.
+            if chunk.0.is_empty() || data_path.0.len() < 8 {
+                return Err(Error::InvalidProof);
+            }
+            let mut note = [0u8; 8];
+            note.copy_from_slice(&data_path.0[data_path.0.len() - 8..]);
+            let max_byte_range = usize::from_be_bytes(note);
+            if max_byte_range != written + chunk.0.len() {
+                return Err(Error::InvalidProof);
+            }
+
+            let leaf = generate_leaf(&chunk.0, written, &self.crypto)?;
+            let proof = Proof {
+                offset: max_byte_range - 1,
+                proof: data_path.0,
+            };
+            validate_chunk(root_id, leaf, proof, &self.crypto)?;
+
+            writer.write_all(&chunk.0).await?;
+            written = max_byte_range;
+        }
+        writer.flush().await?;
+
+        Ok(written as u64)
     }
 
     /// Returns the balance of the wallet.
@@ -464,12 +1033,13 @@ impl Arweave {
         let wallet_address = if let Some(wallet_address) = wallet_address {
             wallet_address
         } else {
-            self.crypto.wallet_address()?.to_string()
+            self.transaction_signer()?.wallet_address()?.to_string()
         };
-        let url = self
-            .base_url
-            .join(&format!("wallet/{}/balance", &wallet_address))?;
-        let winstons = reqwest::get(url).await?.json::<u64>().await?;
+        let winstons = self
+            .get_from_gateways(&format!("wallet/{}/balance", &wallet_address))
+            .await?
+            .json::<u64>()
+            .await?;
         Ok(BigUint::from(winstons))
     }
 
@@ -535,9 +1105,27 @@ impl Arweave {
         paths_iter: Vec<PathBuf>,
         tags: Vec<Tag<String>>,
         price_terms: (u64, u64),
+    ) -> Result<(Transaction, Value), Error> {
+        self.create_bundle_transaction_from_file_paths_with_hashes(
+            paths_iter,
+            tags,
+            &HashMap::new(),
+            price_terms,
+        )
+        .await
+    }
+
+    /// Same as [`Arweave::create_bundle_transaction_from_file_paths`], but tags the data items of
+    /// files in `hashes` with their [`FILE_HASH_TAG`].
+    async fn create_bundle_transaction_from_file_paths_with_hashes(
+        &self,
+        paths_iter: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        hashes: &HashMap<PathBuf, String>,
+        price_terms: (u64, u64),
     ) -> Result<(Transaction, Value), Error> {
         let data_items = self
-            .create_data_items_from_file_paths(paths_iter, tags)
+            .create_data_items_from_file_paths_with_hashes(paths_iter, tags, hashes)
             .await?;
 
         let (bundle, manifest_object) = self.create_bundle_from_data_items(data_items)?;
@@ -553,6 +1141,97 @@ impl Arweave {
         Ok((transaction, manifest_object))
     }
 
+    /// Writes a bundle of [`DataItem`]s created from `paths` to `bundle_path`, reading and signing
+    /// one file at a time, returning the manifest object for the bundle.
+    pub async fn write_bundle_from_file_paths(
+        &self,
+        paths: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        bundle_path: PathBuf,
+    ) -> Result<Value, Error> {
+        self.write_bundle_from_file_paths_with_hashes(paths, tags, &HashMap::new(), bundle_path)
+            .await
+    }
+
+    /// Same as [`Arweave::write_bundle_from_file_paths`], but tags the data items of files in
+    /// `hashes` with their [`FILE_HASH_TAG`].
+    async fn write_bundle_from_file_paths_with_hashes(
+        &self,
+        paths: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        hashes: &HashMap<PathBuf, String>,
+        bundle_path: PathBuf,
+    ) -> Result<Value, Error> {
+        let mut writer = BundleWriter::create(bundle_path, paths.len()).await?;
+        let mut statuses = Vec::with_capacity(paths.len());
+        for path in paths {
+            let tags = with_file_hash_tag(tags.clone(), hashes.get(&path))?;
+            let (data_item, status) = self.create_data_item_from_file_path(path, tags).await?;
+            writer.add_data_item(&data_item).await?;
+            statuses.push(status);
+        }
+        writer.finish().await?;
+        self.create_manifest(statuses)
+    }
+
+    /// Same as [`Arweave::create_bundle_transaction_from_file_paths`], but writes the bundle to
+    /// `bundle_path` and merklizes it from there instead of holding it in memory. Post the
+    /// transaction with [`Arweave::post_transaction_chunks_from_file`].
+    pub async fn create_bundle_transaction_from_file_paths_streaming(
+        &self,
+        paths: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        price_terms: (u64, u64),
+        bundle_path: PathBuf,
+    ) -> Result<(Transaction, Value), Error> {
+        self.create_bundle_transaction_from_file_paths_streaming_with_hashes(
+            paths,
+            tags,
+            &HashMap::new(),
+            price_terms,
+            bundle_path,
+        )
+        .await
+    }
+
+    /// Same as [`Arweave::create_bundle_transaction_from_file_paths_streaming`], but tags the data
+    /// items of files in `hashes` with their [`FILE_HASH_TAG`].
+    async fn create_bundle_transaction_from_file_paths_streaming_with_hashes(
+        &self,
+        paths: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        hashes: &HashMap<PathBuf, String>,
+        price_terms: (u64, u64),
+        bundle_path: PathBuf,
+    ) -> Result<(Transaction, Value), Error> {
+        let manifest_object = self
+            .write_bundle_from_file_paths_with_hashes(paths, tags, hashes, bundle_path.clone())
+            .await?;
+        let other_tags = Some(vec![
+            Tag::<Base64>::from_utf8_strs("Bundle-Format", "binary")?,
+            Tag::<Base64>::from_utf8_strs("Bundle-Version", "2.0.0")?,
+        ]);
+
+        let transaction = self
+            .create_transaction_from_file_path_streaming(
+                bundle_path,
+                other_tags,
+                None,
+                price_terms,
+                true,
+            )
+            .await?;
+
+        Ok((transaction, manifest_object))
+    }
+
+    /// Returns a random path in the system temp directory to write a bundle to.
+    fn temp_bundle_path(&self) -> Result<PathBuf, Error> {
+        let mut rand_bytes = [0u8; 8];
+        self.crypto.fill_rand(&mut rand_bytes)?;
+        Ok(std::env::temp_dir().join(format!("arloader_bundle_{}", Base64(rand_bytes.to_vec()))))
+    }
+
     // Create [`data_item::DataItem`] for bundle.
     pub fn create_data_item(
         &self,
@@ -577,17 +1256,37 @@ impl Arweave {
             tags.push(Tag::<String>::from_utf8_strs("Content-Type", content_type)?)
         }
 
-        // let mut anchor = Base64(Vec::with_capacity(32));
-        // self.crypto.fill_rand(&mut anchor.0)?;
+        let options = &self.data_item_options;
+        let anchor = if options.random_anchor {
+            let mut anchor = Base64(vec![0; 32]);
+            self.crypto.fill_rand(&mut anchor.0)?;
+            anchor
+        } else {
+            options.anchor.clone().unwrap_or_default()
+        };
 
         Ok(DataItem {
             data: Base64(data),
             tags,
-            // anchor,
+            target: options.target.clone().unwrap_or_default(),
+            anchor,
             ..DataItem::default()
         })
     }
 
+    /// Creates a signed [`DataItem`] containing a bundle, tagged so that it gets unpacked as a
+    /// nested bundle, so that bundles can themselves be bundled.
+    pub fn create_data_item_from_bundle(
+        &self,
+        bundle: Vec<u8>,
+        mut tags: Vec<Tag<String>>,
+    ) -> Result<DataItem, Error> {
+        tags.push(Tag::<String>::from_utf8_strs("Bundle-Format", "binary")?);
+        tags.push(Tag::<String>::from_utf8_strs("Bundle-Version", "2.0.0")?);
+        let data_item = self.create_data_item(bundle, tags, false)?;
+        self.sign_data_item(data_item)
+    }
+
     pub async fn create_data_item_from_file_path(
         &self,
         file_path: PathBuf,
@@ -623,63 +1322,301 @@ impl Arweave {
         paths: Vec<PathBuf>,
         tags: Vec<Tag<String>>,
     ) -> Result<Vec<(DataItem, Status)>, Error> {
-        try_join_all(
-            paths
-                .into_iter()
-                .map(|p| self.create_data_item_from_file_path(p, tags.clone())),
-        )
+        self.create_data_items_from_file_paths_with_hashes(paths, tags, &HashMap::new())
+            .await
+    }
+
+    /// Same as [`Arweave::create_data_items_from_file_paths`], but tags the data items of files
+    /// in `hashes` with their [`FILE_HASH_TAG`].
+    pub async fn create_data_items_from_file_paths_with_hashes(
+        &self,
+        paths: Vec<PathBuf>,
+        tags: Vec<Tag<String>>,
+        hashes: &HashMap<PathBuf, String>,
+    ) -> Result<Vec<(DataItem, Status)>, Error> {
+        try_join_all(paths.into_iter().map(|p| async {
+            let tags = with_file_hash_tag(tags.clone(), hashes.get(&p))?;
+            self.create_data_item_from_file_path(p, tags).await
+        }))
         .await
     }
 
+    /// Deserializes a bundle, verifying each [`DataItem`] against its own owner and checking
+    /// that the id in its header is the SHA256 hash of its signature. Data items containing
+    /// nested bundles are followed by the data items of their bundles, up to
+    /// [`MAX_BUNDLE_DEPTH`] bundles deep.
     // Tested here instead of data_item to verify signature as well - crytpo on data_item.
     pub fn deserialize_bundle(&self, bundle: Vec<u8>) -> Result<Vec<DataItem>, Error> {
-        let mut bundle_iter = bundle.into_iter();
-        let result = [(); 8].map(|_| bundle_iter.next().unwrap());
-        let number_of_data_items = u64::from_le_bytes(result) as usize;
-        (0..24).for_each(|_| {
-            bundle_iter.next().unwrap();
-        });
-
-        // Parse headers.
-        let mut bytes_lens = Vec::<u64>::with_capacity(number_of_data_items);
-        let mut ids = vec![Vec::<u8>::with_capacity(32); number_of_data_items];
-        (0..number_of_data_items).for_each(|i| {
-            let result = [(); 8].map(|_| bundle_iter.next().unwrap());
-            bytes_lens.push(u64::from_le_bytes(result));
-            (0..24).for_each(|_| {
-                bundle_iter.next().unwrap();
-            });
-            (0..32).for_each(|_| {
-                ids[i].push(bundle_iter.next().unwrap());
-            });
-        });
-
-        // Parse data_items - data_item verified during deserialization - signatures verified
-        // TODO: verify signature against data_item id.
-        let mut bytes_lens_iter = bytes_lens.into_iter();
-        let mut ids_iter = ids.into_iter();
-        let data_items: Result<Vec<DataItem>, _> = (0..number_of_data_items)
-            .map(|_| {
-                let bytes_len = bytes_lens_iter.next().unwrap() as usize;
-                let mut bytes_vec = Vec::<u8>::with_capacity(bytes_len);
-                (0..bytes_len).for_each(|_| bytes_vec.push(bundle_iter.next().unwrap()));
-                let mut data_item = DataItem::deserialize(bytes_vec)?;
+        self.deserialize_nested_bundle(&bundle, 1)
+    }
 
-                let deep_hash = self
-                    .crypto
-                    .deep_hash(data_item.to_deep_hash_item()?)
-                    .unwrap();
-                self.crypto
-                    .verify(&data_item.signature.0, &deep_hash)
-                    .unwrap();
+    /// Deserializes a bundle nested in `depth - 1` others for [`Arweave::deserialize_bundle`].
+    fn deserialize_nested_bundle(
+        &self,
+        bundle: &[u8],
+        depth: usize,
+    ) -> Result<Vec<DataItem>, Error> {
+        if depth > MAX_BUNDLE_DEPTH {
+            return Err(Error::BundleTooDeep(MAX_BUNDLE_DEPTH));
+        }
+        let mut data_items = Vec::new();
+        for (id, binary) in split_bundle(bundle)? {
+            let mut data_item = DataItem::deserialize_slice(binary)?;
+            data_item.id = id;
+            let (valid_signature, valid_id) = self.verify_data_item(&data_item);
+            if !(valid_signature && valid_id) {
+                return Err(Error::InvalidSignature);
+            }
+            let nested = if data_item.is_bundle() {
+                self.deserialize_nested_bundle(&data_item.data.0, depth + 1)?
+            } else {
+                Vec::new()
+            };
+            data_items.push(data_item);
+            data_items.extend(nested);
+        }
+        Ok(data_items)
+    }
+
+    /// Verifies each [`DataItem`] in a bundle against its own owner, returning a
+    /// [`DataItemReport`] for each item rather than failing on the first invalid one. Reports for
+    /// the data items of nested bundles follow the report of the data item containing them, up to
+    /// [`MAX_BUNDLE_DEPTH`] bundles deep. Only returns an error if the bundle headers can't be
+    /// read.
+    pub fn verify_bundle(&self, bundle: &[u8]) -> Result<Vec<DataItemReport>, Error> {
+        self.verify_nested_bundle(bundle, 1)
+    }
+
+    /// Verifies a bundle nested in `depth - 1` others for [`Arweave::verify_bundle`].
+    fn verify_nested_bundle(
+        &self,
+        bundle: &[u8],
+        depth: usize,
+    ) -> Result<Vec<DataItemReport>, Error> {
+        if depth > MAX_BUNDLE_DEPTH {
+            return Err(Error::BundleTooDeep(MAX_BUNDLE_DEPTH));
+        }
+        let mut reports = Vec::new();
+        for (id, binary) in split_bundle(bundle)? {
+            reports.extend(self.data_item_reports(id, DataItem::deserialize_slice(binary), depth));
+        }
+        Ok(reports)
+    }
+
+    /// Same as [`Arweave::verify_bundle`], but reads the bundle from `reader` with a
+    /// [`BundleReader`], one data item at a time, so that large bundles aren't read into memory.
+    pub fn verify_bundle_from_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<DataItemReport>, Error> {
+        let mut reader = BundleReader::new(reader)?;
+        let mut reports = Vec::new();
+        for entry in reader.entries()? {
+            let data_item = reader.read_data_item(&entry);
+            reports.extend(self.data_item_reports(entry.id, data_item, 1));
+        }
+        Ok(reports)
+    }
+
+    /// Returns the report for a data item with id `id` read from a bundle nested in `depth - 1`
+    /// others, followed by the reports for the data items of its own bundle, if it has one.
+    fn data_item_reports(
+        &self,
+        id: Base64,
+        data_item: Result<DataItem, Error>,
+        depth: usize,
+    ) -> Vec<DataItemReport> {
+        match data_item {
+            Ok(mut data_item) => {
+                data_item.id = id;
+                let mut report = self.data_item_report(&data_item);
+                let nested = if data_item.is_bundle() {
+                    self.verify_nested_bundle(&data_item.data.0, depth + 1)
+                        .unwrap_or_else(|e| {
+                            report.error = Some(format!("nested bundle: {}", e));
+                            Vec::new()
+                        })
+                } else {
+                    Vec::new()
+                };
+                std::iter::once(report).chain(nested).collect()
+            }
+            Err(e) => vec![DataItemReport {
+                id,
+                owner: Base64::default(),
+                content_type: String::new(),
+                data_size: 0,
+                tags: Vec::new(),
+                valid_signature: false,
+                valid_id: false,
+                error: Some(e.to_string()),
+            }],
+        }
+    }
 
-                data_item.id.0 = ids_iter.next().unwrap();
+    /// Writes the data of each [`DataItem`] in a bundle to `dir`, named by its path in a manifest
+    /// included in the bundle, if there is one, or otherwise by its id. Returns the id and the
+    /// file path written for each data item.
+    pub async fn extract_bundle(
+        &self,
+        bundle: Vec<u8>,
+        dir: PathBuf,
+    ) -> Result<Vec<(Base64, PathBuf)>, Error> {
+        self.extract_bundle_from_reader(Cursor::new(bundle), dir)
+            .await
+    }
 
-                Ok(data_item)
+    /// Same as [`Arweave::extract_bundle`], but reads the bundle from `reader` with a
+    /// [`BundleReader`], one data item at a time, so that large bundles aren't read into memory.
+    pub async fn extract_bundle_from_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+        dir: PathBuf,
+    ) -> Result<Vec<(Base64, PathBuf)>, Error> {
+        let is_manifest = |data_item: &DataItem| {
+            data_item.tags.iter().any(|t| {
+                t.name == "Content-Type" && t.value == "application/x.arweave-manifest+json"
             })
-            .collect();
+        };
+        let mut reader = BundleReader::new(reader)?;
+        let entries = reader.entries()?;
+
+        // Map ids to paths from any manifests in the bundle, ignoring paths that would
+        // escape `dir`. Only manifests and nested bundles that may contain them are read.
+        let mut paths = HashMap::new();
+        for entry in &entries {
+            let header = reader.read_header(entry)?;
+            if !(is_manifest(&header.data_item) || header.data_item.is_bundle()) {
+                continue;
+            }
+            for data_item in self.read_verified_data_items(&mut reader, entry)? {
+                if !is_manifest(&data_item) {
+                    continue;
+                }
+                let manifest: Value = serde_json::from_slice(&data_item.data.0)?;
+                if let Some(manifest_paths) = manifest["paths"].as_object() {
+                    for (path, value) in manifest_paths {
+                        let path = PathBuf::from(path);
+                        let is_relative = path
+                            .components()
+                            .all(|c| matches!(c, std::path::Component::Normal(_)));
+                        if let (Some(id), true) = (value["id"].as_str(), is_relative) {
+                            paths.insert(id.to_string(), path);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut extracted = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            for data_item in self.read_verified_data_items(&mut reader, entry)? {
+                let id = data_item.id.to_string();
+                let file_path = dir.join(paths.remove(&id).unwrap_or_else(|| PathBuf::from(&id)));
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::write(&file_path, &data_item.data.0).await?;
+                extracted.push((data_item.id, file_path));
+            }
+        }
+        Ok(extracted)
+    }
+
+    /// Reads the [`DataItem`] for `entry` from `reader`, verifying it as
+    /// [`Arweave::deserialize_bundle`] does, followed by the data items of its own bundle, if it
+    /// has one.
+    fn read_verified_data_items<R: Read + Seek>(
+        &self,
+        reader: &mut BundleReader<R>,
+        entry: &BundleEntry,
+    ) -> Result<Vec<DataItem>, Error> {
+        let data_item = reader.read_data_item(entry)?;
+        let (valid_signature, valid_id) = self.verify_data_item(&data_item);
+        if !(valid_signature && valid_id) {
+            return Err(Error::InvalidSignature);
+        }
+        let nested = if data_item.is_bundle() {
+            self.deserialize_nested_bundle(&data_item.data.0, 2)?
+        } else {
+            Vec::new()
+        };
+        Ok(std::iter::once(data_item).chain(nested).collect())
+    }
 
-        data_items
+    /// Verifies the signature of a [`DataItem`] against its `owner` and that its `id` is the
+    /// SHA256 hash of its signature, returning `(valid_signature, valid_id)`.
+    pub fn verify_data_item(&self, data_item: &DataItem) -> (bool, bool) {
+        let valid_signature = data_item
+            .to_deep_hash_item()
+            .and_then(|item| self.crypto.deep_hash(item))
+            .and_then(|deep_hash| {
+                verify_signature(
+                    data_item.signature_type,
+                    &data_item.owner.0,
+                    &data_item.signature.0,
+                    &deep_hash,
+                )
+            })
+            .is_ok();
+        let valid_id = self
+            .crypto
+            .hash_sha256(&data_item.signature.0)
+            .map(|hash| hash.to_vec() == data_item.id.0)
+            .unwrap_or(false);
+        (valid_signature, valid_id)
+    }
+
+    /// Creates a [`DataItemReport`] for a deserialized [`DataItem`].
+    pub fn data_item_report(&self, data_item: &DataItem) -> DataItemReport {
+        let (valid_signature, valid_id) = self.verify_data_item(data_item);
+        let content_type = data_item
+            .tags
+            .iter()
+            .find(|t| t.name == "Content-Type")
+            .map(|t| t.value.clone())
+            .unwrap_or_else(|| mime_guess::mime::OCTET_STREAM.to_string());
+        let owner = self
+            .crypto
+            .hash_sha256(&data_item.owner.0)
+            .map(|hash| Base64(hash.to_vec()))
+            .unwrap_or_default();
+
+        DataItemReport {
+            id: data_item.id.clone(),
+            owner,
+            content_type,
+            data_size: data_item.data.0.len() as u64,
+            tags: data_item.tags.clone(),
+            valid_signature,
+            valid_id,
+            error: None,
+        }
+    }
+
+    /// Returns the paths of `manifest` with the files in `uploaded` added to them.
+    fn with_uploaded_paths(&self, manifest: Value, uploaded: Vec<Status>) -> Result<Value, Error> {
+        let mut paths = manifest["paths"].clone();
+        if let (Some(paths), Value::Object(uploaded)) = (
+            paths.as_object_mut(),
+            self.create_manifest(uploaded)?["paths"].take(),
+        ) {
+            paths.extend(uploaded);
+        }
+        Ok(paths)
+    }
+
+    /// Returns a [`BundleStatus`] marked `deduplicated` for a chunk of files all found already
+    /// uploaded, for which no bundle gets posted, recording the ids of the files.
+    fn uploaded_bundle_status(&self, uploaded: Vec<Status>) -> Result<BundleStatus, Error> {
+        let (id, status) = (uploaded[0].id.clone(), uploaded[0].status.clone());
+        Ok(BundleStatus {
+            id,
+            status,
+            file_paths: self.create_manifest(uploaded)?["paths"].take(),
+            deduplicated: true,
+            ..Default::default()
+        })
     }
 
     pub async fn post_bundle_transaction_from_file_paths(
@@ -689,28 +1626,50 @@ impl Arweave {
         price_terms: (u64, u64),
         buffer: usize,
     ) -> Result<BundleStatus, Error> {
+        let (paths_chunk, hashes, uploaded) = self.split_uploaded_files(paths_chunk).await?;
+        if paths_chunk.0.is_empty() {
+            return self.uploaded_bundle_status(uploaded);
+        }
         let number_of_files = paths_chunk.0.len() as u64;
-        let data_items = self
-            .create_data_items_from_file_paths(paths_chunk.0, tags)
-            .await?;
-
-        let (bundle, manifest) = self.create_bundle_from_data_items(data_items)?;
-        let other_tags = Some(vec![
-            Tag::<Base64>::from_utf8_strs("Bundle-Format", "binary")?,
-            Tag::<Base64>::from_utf8_strs("Bundle-Version", "2.0.0")?,
-        ]);
-
-        let transaction = self
-            .create_transaction(bundle, other_tags, None, price_terms, true)
-            .await?;
-
-        let signed_transaction = self.sign_transaction(transaction)?;
 
-        let (id, reward) = if paths_chunk.1 > MAX_TX_DATA {
-            self.post_transaction_chunks(signed_transaction, buffer)
-                .await?
+        // Large bundles are written to a temp file and posted from there to keep memory bounded.
+        let ((id, reward), manifest) = if paths_chunk.1 > MAX_TX_DATA {
+            let bundle_path = self.temp_bundle_path()?;
+            let result = async {
+                let (transaction, manifest) = self
+                    .create_bundle_transaction_from_file_paths_streaming_with_hashes(
+                        paths_chunk.0,
+                        tags,
+                        &hashes,
+                        price_terms,
+                        bundle_path.clone(),
+                    )
+                    .await?;
+                let signed_transaction = self.sign_transaction(transaction)?;
+                let posted = self
+                    .post_transaction_chunks_from_file(
+                        signed_transaction,
+                        bundle_path.clone(),
+                        buffer,
+                        None,
+                    )
+                    .await?;
+                Ok::<_, Error>((posted, manifest))
+            }
+            .await;
+            let _ = fs::remove_file(&bundle_path).await;
+            result?
         } else {
-            self.post_transaction(&signed_transaction).await?
+            let (transaction, manifest) = self
+                .create_bundle_transaction_from_file_paths_with_hashes(
+                    paths_chunk.0,
+                    tags,
+                    &hashes,
+                    price_terms,
+                )
+                .await?;
+            let signed_transaction = self.sign_transaction(transaction)?;
+            (self.post_transaction(&signed_transaction).await?, manifest)
         };
 
         let status = BundleStatus {
@@ -718,7 +1677,7 @@ impl Arweave {
             reward,
             number_of_files,
             data_size: paths_chunk.1,
-            file_paths: manifest["paths"].clone(),
+            file_paths: self.with_uploaded_paths(manifest, uploaded)?,
             ..Default::default()
         };
 
@@ -735,30 +1694,58 @@ impl Arweave {
         sol_ar_url: Url,
         from_keypair: &Keypair,
     ) -> Result<BundleStatus, Error> {
+        let (paths_chunk, hashes, uploaded) = self.split_uploaded_files(paths_chunk).await?;
+        if paths_chunk.0.is_empty() {
+            return self.uploaded_bundle_status(uploaded);
+        }
         let number_of_files = paths_chunk.0.len() as u64;
-        let data_items = self
-            .create_data_items_from_file_paths(paths_chunk.0, tags)
-            .await?;
-
-        let (bundle, manifest) = self.create_bundle_from_data_items(data_items)?;
-        let other_tags = Some(vec![
-            Tag::<Base64>::from_utf8_strs("Bundle-Format", "binary")?,
-            Tag::<Base64>::from_utf8_strs("Bundle-Version", "2.0.0")?,
-        ]);
-
-        let transaction = self
-            .create_transaction(bundle, other_tags, None, price_terms, true)
-            .await?;
-
-        let (signed_transaction, sig_response): (Transaction, SigResponse) = self
-            .sign_transaction_with_sol(transaction, solana_url, sol_ar_url, from_keypair)
-            .await?;
 
-        let (id, reward) = if paths_chunk.1 > MAX_TX_DATA {
-            self.post_transaction_chunks(signed_transaction, chunks_buffer)
-                .await?
+        // Large bundles are written to a temp file and posted from there to keep memory bounded.
+        let ((id, reward), manifest, sig_response) = if paths_chunk.1 > MAX_TX_DATA {
+            let bundle_path = self.temp_bundle_path()?;
+            let result = async {
+                let (transaction, manifest) = self
+                    .create_bundle_transaction_from_file_paths_streaming_with_hashes(
+                        paths_chunk.0,
+                        tags,
+                        &hashes,
+                        price_terms,
+                        bundle_path.clone(),
+                    )
+                    .await?;
+                let (signed_transaction, sig_response): (Transaction, SigResponse) = self
+                    .sign_transaction_with_sol(transaction, solana_url, sol_ar_url, from_keypair)
+                    .await?;
+                let posted = self
+                    .post_transaction_chunks_from_file(
+                        signed_transaction,
+                        bundle_path.clone(),
+                        chunks_buffer,
+                        None,
+                    )
+                    .await?;
+                Ok::<_, Error>((posted, manifest, sig_response))
+            }
+            .await;
+            let _ = fs::remove_file(&bundle_path).await;
+            result?
         } else {
-            self.post_transaction(&signed_transaction).await?
+            let (transaction, manifest) = self
+                .create_bundle_transaction_from_file_paths_with_hashes(
+                    paths_chunk.0,
+                    tags,
+                    &hashes,
+                    price_terms,
+                )
+                .await?;
+            let (signed_transaction, sig_response): (Transaction, SigResponse) = self
+                .sign_transaction_with_sol(transaction, solana_url, sol_ar_url, from_keypair)
+                .await?;
+            (
+                self.post_transaction(&signed_transaction).await?,
+                manifest,
+                sig_response,
+            )
         };
 
         let status = BundleStatus {
@@ -766,7 +1753,7 @@ impl Arweave {
             reward,
             number_of_files,
             data_size: paths_chunk.1,
-            file_paths: manifest["paths"].clone(),
+            file_paths: self.with_uploaded_paths(manifest, uploaded)?,
             sol_sig: Some(sig_response),
             ..Default::default()
         };
@@ -775,10 +1762,12 @@ impl Arweave {
     }
 
     pub fn sign_data_item(&self, mut data_item: DataItem) -> Result<DataItem, Error> {
-        data_item.owner = self.crypto.keypair_modulus()?;
+        let signer = self.signer();
+        data_item.signature_type = signer.signature_type();
+        data_item.owner = signer.owner()?;
         let deep_hash_item = data_item.to_deep_hash_item()?;
         let deep_hash = self.crypto.deep_hash(deep_hash_item)?;
-        let signature = self.crypto.sign(&deep_hash)?;
+        let signature = signer.sign(&deep_hash)?;
         let id = self.crypto.hash_sha256(&signature)?;
 
         data_item.signature = Base64(signature);
@@ -786,6 +1775,69 @@ impl Arweave {
         Ok(data_item)
     }
 
+    /// Posts a signed [`DataItem`] to the `tx` endpoint of a bundler, which includes it in a
+    /// bundle transaction, returning the bundler's receipt.
+    pub async fn upload_data_item_to_bundler(
+        &self,
+        bundler_url: &Url,
+        data_item: &DataItem,
+    ) -> Result<BundlerReceipt, Error> {
+        let url = bundler_url.join("tx")?;
+        let body = data_item.serialize()?;
+        self.rate_limiter.acquire(body.len()).await;
+        let mut request = self
+            .client
+            .post(url)
+            .headers(self.client_options.headers.clone())
+            .header(&ACCEPT, "application/json")
+            .header(&CONTENT_TYPE, "application/octet-stream");
+        if let Some(timeout) = self.client_options.upload_timeout(body.len()) {
+            request = request.timeout(timeout);
+        }
+        let resp = request.body(body).send().await?;
+
+        if resp.status().is_success() {
+            Ok(resp.json().await?)
+        } else {
+            Err(Error::BundlerPostError(resp.status()))
+        }
+    }
+
+    /// Creates a signed [`DataItem`] from a file and posts it to a bundler, recording the receipt
+    /// in the returned [`Status`], which is written to `log_dir` if provided. With `dedupe` set,
+    /// files already uploaded get a [`Status`] with the existing id and aren't posted.
+    pub async fn upload_file_to_bundler(
+        &self,
+        file_path: PathBuf,
+        mut tags: Vec<Tag<String>>,
+        bundler_url: &Url,
+        log_dir: Option<PathBuf>,
+    ) -> Result<Status, Error> {
+        if self.dedupe {
+            let (hash, uploaded) = self.find_uploaded_file(&file_path, self.signer()).await?;
+            if let Some(uploaded) = uploaded {
+                let status = uploaded_file_status(file_path, uploaded);
+                if let Some(log_dir) = log_dir {
+                    self.write_status(status.clone(), log_dir, None).await?;
+                }
+                return Ok(status);
+            }
+            tags.push(Tag::<String>::from_utf8_strs(FILE_HASH_TAG, &hash)?);
+        }
+        let (data_item, mut status) = self
+            .create_data_item_from_file_path(file_path, tags)
+            .await?;
+        let receipt = self
+            .upload_data_item_to_bundler(bundler_url, &data_item)
+            .await?;
+        status.bundler_receipt = Some(receipt);
+
+        if let Some(log_dir) = log_dir {
+            self.write_status(status.clone(), log_dir, None).await?;
+        }
+        Ok(status)
+    }
+
     //-------------------------
     // Transaction
     //-------------------------
@@ -798,23 +1850,108 @@ impl Arweave {
         price_terms: (u64, u64),
         auto_content_tag: bool,
     ) -> Result<Transaction, Error> {
-        let mut transaction = self.merklize(data)?;
-        transaction.owner = self.crypto.keypair_modulus()?;
+        let transaction = self.merklize(data)?;
+        let content_type = if auto_content_tag {
+            Some(infer_content_type(&transaction.data.0))
+        } else {
+            None
+        };
+        self.complete_transaction(transaction, other_tags, last_tx, price_terms, content_type)
+            .await
+    }
+
+    /// Creates a [`Transaction`] transferring `quantity` winstons to the wallet at `target`, with
+    /// optional `data`. The reward is fetched from `price/{data_size}/{target}`, which includes
+    /// the fee for transferring to a new wallet, if not provided.
+    pub async fn create_transfer_transaction(
+        &self,
+        target: Base64,
+        quantity: u64,
+        data: Vec<u8>,
+        other_tags: Option<Vec<Tag<Base64>>>,
+        last_tx: Option<Base64>,
+        reward: Option<u64>,
+    ) -> Result<Transaction, Error> {
+        let transaction = if data.is_empty() {
+            Transaction {
+                format: 2,
+                ..Default::default()
+            }
+        } else {
+            self.merklize(data)?
+        };
+        let reward = match reward {
+            Some(reward) => reward,
+            None => {
+                self.get_transfer_reward(transaction.data_size, &target)
+                    .await?
+            }
+        };
+        let mut transaction = self
+            .complete_transaction(transaction, other_tags, last_tx, (reward, 0), None)
+            .await?;
+        transaction.target = target;
+        transaction.quantity = quantity;
+        Ok(transaction)
+    }
+
+    /// Gets the reward in winstons for a transaction with `bytes` of data sent to `target`.
+    pub async fn get_transfer_reward(&self, bytes: u64, target: &Base64) -> Result<u64, Error> {
+        let winstons = self
+            .get_from_gateways(&format!("price/{}/{}", bytes, target))
+            .await
+            .map_err(|e| match e {
+                Error::Reqwest(e) => Error::ArweaveGetPriceError(e),
+                e => e,
+            })?
+            .json::<u64>()
+            .await?;
+        Ok(winstons)
+    }
+
+    /// Creates a format 1 [`Transaction`], with `data` held inline instead of being merklized and
+    /// posted in chunks. Nodes only accept format 1 transactions with up to 10 MB of data.
+    pub async fn create_format_1_transaction(
+        &self,
+        data: Vec<u8>,
+        other_tags: Option<Vec<Tag<Base64>>>,
+        last_tx: Option<Base64>,
+        price_terms: (u64, u64),
+        auto_content_tag: bool,
+    ) -> Result<Transaction, Error> {
+        let content_type = if auto_content_tag {
+            Some(infer_content_type(&data))
+        } else {
+            None
+        };
+        let transaction = Transaction {
+            format: 1,
+            data_size: data.len() as u64,
+            data: Base64(data),
+            ..Default::default()
+        };
+        self.complete_transaction(transaction, other_tags, last_tx, price_terms, content_type)
+            .await
+    }
+
+    /// Sets owner, tags, `last_tx` and reward on a merklized [`Transaction`], adding a
+    /// `Content-Type` tag if `content_type` is provided.
+    async fn complete_transaction(
+        &self,
+        mut transaction: Transaction,
+        other_tags: Option<Vec<Tag<Base64>>>,
+        last_tx: Option<Base64>,
+        price_terms: (u64, u64),
+        content_type: Option<&str>,
+    ) -> Result<Transaction, Error> {
+        transaction.owner = self.transaction_signer()?.owner()?;
 
         let mut tags = vec![Tag::<Base64>::from_utf8_strs(
             "User-Agent",
             &format!("arloader/{}", VERSION),
         )?];
 
-        // Get content type from [magic numbers](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types)
-        // and include additional tags if any.
-        if auto_content_tag {
-            let content_type = if let Some(kind) = infer::get(&transaction.data.0) {
-                kind.mime_type()
-            } else {
-                "application/octet-stream"
-            };
-
+        if let Some(content_type) = content_type {
             tags.push(Tag::<Base64>::from_utf8_strs("Content-Type", content_type)?)
         }
 
@@ -828,7 +1965,7 @@ impl Arweave {
         let last_tx = if let Some(last_tx) = last_tx {
             last_tx
         } else {
-            let resp = reqwest::get(self.base_url.join("tx_anchor")?).await?;
+            let resp = self.get_from_gateways("tx_anchor").await?;
             debug!("last_tx: {}", resp.status());
             let last_tx_str = resp.text().await?;
             Base64::from_str(&last_tx_str)?
@@ -837,7 +1974,7 @@ impl Arweave {
 
         let blocks_len =
             transaction.data_size / BLOCK_SIZE + (transaction.data_size % BLOCK_SIZE != 0) as u64;
-        let reward = price_terms.0 + price_terms.1 * (blocks_len - 1);
+        let reward = price_terms.0 + price_terms.1 * blocks_len.saturating_sub(1);
         transaction.reward = reward;
 
         Ok(transaction)
@@ -856,6 +1993,37 @@ impl Arweave {
             .await
     }
 
+    /// Creates a [`Transaction`] from a file without reading the whole file into memory.
+    ///
+    /// The returned transaction has no `data`, so chunks have to be read back from `file_path`
+    /// when they get posted with [`Arweave::post_transaction_chunks_from_file`].
+    pub async fn create_transaction_from_file_path_streaming(
+        &self,
+        file_path: PathBuf,
+        other_tags: Option<Vec<Tag<Base64>>>,
+        last_tx: Option<Base64>,
+        price_terms: (u64, u64),
+        auto_content_tag: bool,
+    ) -> Result<Transaction, Error> {
+        let content_type = if auto_content_tag {
+            let mut sample = Vec::with_capacity(8192);
+            fs::File::open(&file_path)
+                .await?
+                .take(8192)
+                .read_to_end(&mut sample)
+                .await?;
+            Some(infer_content_type(&sample))
+        } else {
+            None
+        };
+
+        let transaction = self
+            .merklize_reader(fs::File::open(&file_path).await?)
+            .await?;
+        self.complete_transaction(transaction, other_tags, last_tx, price_terms, content_type)
+            .await
+    }
+
     pub fn merklize(&self, data: Vec<u8>) -> Result<Transaction, Error> {
         let mut chunks = generate_leaves(data.clone(), &self.crypto)?;
         let root = generate_data_root(chunks.clone(), &self.crypto)?;
@@ -880,22 +2048,65 @@ impl Arweave {
         })
     }
 
-    pub async fn post_chunk(&self, chunk: &Chunk, client: &Client) -> Result<usize, Error> {
-        let url = self.base_url.join("chunk")?;
-        // let client = reqwest::Client::new();
+    /// Streaming version of [`Arweave::merklize`]. Reads data from `reader` a chunk at a time and
+    /// returns a [`Transaction`] with `data_root`, `data_size`, `chunks` and `proofs` set, but
+    /// without `data`.
+    pub async fn merklize_reader<R>(&self, reader: R) -> Result<Transaction, Error>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let (mut chunks, data_size) = generate_leaves_from_reader(reader, &self.crypto).await?;
+        let root = generate_data_root(chunks.clone(), &self.crypto)?;
+        let data_root = Base64(root.id.into_iter().collect());
+        let mut proofs = resolve_proofs(root, None)?;
 
-        let resp = client
-            .post(url)
-            .json(&chunk)
-            .header(&ACCEPT, "application/json")
-            .header(&CONTENT_TYPE, "application/json")
-            .send()
+        // Discard the last chunk & proof if it's zero length.
+        let last_chunk = chunks.last().unwrap();
+        if last_chunk.max_byte_range == last_chunk.min_byte_range {
+            chunks.pop();
+            proofs.pop();
+        }
+
+        Ok(Transaction {
+            format: 2,
+            data_size,
+            data_root,
+            chunks,
+            proofs,
+            ..Default::default()
+        })
+    }
+
+    pub async fn post_chunk(&self, chunk: &Chunk, client: &Client) -> Result<usize, Error> {
+        let body = serde_json::to_vec(chunk)?;
+
+        // Chunks are spread across healthy gateways rather than always going to `base_url` first.
+        let resp = self
+            .gateways
+            .send(
+                &self.base_url,
+                "chunk",
+                true,
+                &self.rate_limiter,
+                body.len(),
+                |url| {
+                    client
+                        .post(url)
+                        .body(body.clone())
+                        .headers(self.client_options.headers.clone())
+                        .header(&ACCEPT, "application/json")
+                        .header(&CONTENT_TYPE, "application/json")
+                },
+            )
             .await
-            .map_err(|e| Error::ArweavePostError(e))?;
+            .map_err(|e| match e {
+                Error::Reqwest(e) => Error::ArweavePostError(e),
+                e => e,
+            })?;
 
         match resp.status() {
             reqwest::StatusCode::OK => Ok(chunk.offset),
-            _ => Err(Error::StatusCodeNotOk),
+            status => Err(Error::ArweaveNetworkError(status)),
         }
     }
 
@@ -904,21 +2115,9 @@ impl Arweave {
         chunk: Chunk,
         client: Client,
     ) -> Result<usize, Error> {
-        let mut retries = 0;
-        let mut resp = self.post_chunk(&chunk, &client).await;
-
-        while retries < CHUNKS_RETRIES {
-            match resp {
-                Ok(offset) => return Ok(offset),
-                Err(e) => {
-                    log::debug!("post_chunk_with_retries: {:?}", e);
-                    sleep(Duration::from_secs(CHUNKS_RETRY_SLEEP)).await;
-                    retries += 1;
-                    resp = self.post_chunk(&chunk, &client).await;
-                }
-            }
-        }
-        resp
+        self.retry_policy
+            .retry(|| self.post_chunk(&chunk, &client))
+            .await
     }
 
     pub async fn post_transaction(
@@ -929,29 +2128,27 @@ impl Arweave {
             return Err(error::Error::UnsignedTransaction.into());
         }
 
-        let mut retries = 0;
-        let mut status = reqwest::StatusCode::NOT_FOUND;
-        let url = self.base_url.join("tx")?;
-        let client = reqwest::Client::new();
-
-        while (retries < CHUNKS_RETRIES) & (status != reqwest::StatusCode::OK) {
-            status = client
-                .post(url.clone())
-                .json(&signed_transaction)
-                .header(&ACCEPT, "application/json")
-                .header(&CONTENT_TYPE, "application/json")
-                .send()
-                .await?
-                .status();
-            if status == reqwest::StatusCode::OK {
-                return Ok((signed_transaction.id.clone(), signed_transaction.reward));
-            }
-            log::debug!("post_transaction: {:?}", status);
-            sleep(Duration::from_secs(CHUNKS_RETRY_SLEEP)).await;
-            retries += 1;
-        }
-
-        Err(Error::StatusCodeNotOk)
+        let body = serde_json::to_vec(signed_transaction)?;
+        self.retry_policy
+            .retry(|| async {
+                let resp = self
+                    .send_to_gateways("tx", body.len(), |url| {
+                        self.client
+                            .post(url)
+                            .body(body.clone())
+                            .header(&ACCEPT, "application/json")
+                            .header(&CONTENT_TYPE, "application/json")
+                    })
+                    .await?;
+                match resp.status() {
+                    // A retry after a post that timed out but went through gets 208.
+                    reqwest::StatusCode::OK | reqwest::StatusCode::ALREADY_REPORTED => {
+                        Ok((signed_transaction.id.clone(), signed_transaction.reward))
+                    }
+                    status => Err(Error::ArweaveNetworkError(status)),
+                }
+            })
+            .await
     }
 
     pub async fn post_transaction_chunks(
@@ -976,13 +2173,180 @@ impl Arweave {
         Ok((id, reward))
     }
 
-    /// Gets deep hash, signs and sets signature and id.
-    pub fn sign_transaction(&self, mut transaction: Transaction) -> Result<Transaction, Error> {
-        let deep_hash_item = transaction.to_deep_hash_item()?;
-        let deep_hash = self.crypto.deep_hash(deep_hash_item)?;
-        let signature = self.crypto.sign(&deep_hash)?;
-        let id = self.crypto.hash_sha256(&signature)?;
-        transaction.signature = Base64(signature);
+    /// Posts a transaction without data, followed by its chunks, which are read from `file_path`
+    /// as they get posted.
+    ///
+    /// If `log_dir` is provided, progress is written to an [`Uploader`] state file so that chunks
+    /// that fail to post can be retried later with [`Arweave::resume_upload`].
+    pub async fn post_transaction_chunks_from_file(
+        &self,
+        signed_transaction: Transaction,
+        file_path: PathBuf,
+        chunks_buffer: usize,
+        log_dir: Option<PathBuf>,
+    ) -> Result<(Base64, u64), Error> {
+        if signed_transaction.id.0.is_empty() {
+            return Err(error::Error::UnsignedTransaction);
+        }
+
+        let mut uploader = Uploader {
+            transaction: signed_transaction.clone_with_no_data()?,
+            file_path: Some(file_path),
+            number_of_chunks: signed_transaction.chunks.len(),
+            ..Default::default()
+        };
+        if let Some(log_dir) = &log_dir {
+            self.write_uploader(&uploader, log_dir.clone()).await?;
+        }
+
+        let (id, reward) = self.post_transaction(&uploader.transaction).await?;
+        uploader.tx_posted = true;
+
+        self.post_uploader_chunks(&mut uploader, signed_transaction, chunks_buffer, log_dir)
+            .await?;
+
+        Ok((id, reward))
+    }
+
+    /// Posts a [`Transaction`] signed on another machine along with the chunks of its data read
+    /// from `file_path`, returning [`Error::DataRootMismatch`] if the file doesn't match the
+    /// transaction's data root.
+    pub async fn post_signed_transaction_from_file(
+        &self,
+        signed_transaction: Transaction,
+        file_path: PathBuf,
+        chunks_buffer: usize,
+        log_dir: Option<PathBuf>,
+    ) -> Result<(Base64, u64), Error> {
+        if signed_transaction.id.0.is_empty() {
+            return Err(Error::UnsignedTransaction);
+        }
+
+        let merklized = self
+            .merklize_reader(fs::File::open(&file_path).await?)
+            .await?;
+        if merklized.data_root != signed_transaction.data_root {
+            return Err(Error::DataRootMismatch);
+        }
+
+        let mut signed_transaction = signed_transaction.clone_with_no_data()?;
+        signed_transaction.chunks = merklized.chunks;
+        signed_transaction.proofs = merklized.proofs;
+        self.post_transaction_chunks_from_file(
+            signed_transaction,
+            file_path,
+            chunks_buffer,
+            log_dir,
+        )
+        .await
+    }
+
+    /// Resumes an upload from the [`Uploader`] state file for transaction `id` in `log_dir`,
+    /// posting the transaction if it hadn't been posted and then only the chunks still missing.
+    ///
+    /// Chunks are re-created from the original file, which must still match the transaction's
+    /// `data_root`.
+    pub async fn resume_upload(
+        &self,
+        id: &Base64,
+        log_dir: PathBuf,
+        chunks_buffer: usize,
+    ) -> Result<Uploader, Error> {
+        let mut uploader = self.read_uploader(id, log_dir.clone()).await?;
+        if uploader.is_complete() {
+            return Ok(uploader);
+        }
+
+        let file_path = uploader.file_path.clone().ok_or(Error::MissingFilePath)?;
+        let merklized = self
+            .merklize_reader(fs::File::open(&file_path).await?)
+            .await?;
+        if merklized.data_root != uploader.transaction.data_root {
+            return Err(Error::DataRootMismatch);
+        }
+
+        let mut signed_transaction = uploader.transaction.clone_with_no_data()?;
+        signed_transaction.chunks = merklized.chunks;
+        signed_transaction.proofs = merklized.proofs;
+
+        if !uploader.tx_posted {
+            self.post_transaction(&uploader.transaction).await?;
+            uploader.tx_posted = true;
+        }
+
+        self.post_uploader_chunks(
+            &mut uploader,
+            signed_transaction,
+            chunks_buffer,
+            Some(log_dir),
+        )
+        .await?;
+
+        Ok(uploader)
+    }
+
+    /// Posts chunks missing from `uploader`, writing progress to `log_dir` at most every
+    /// [`UPLOADER_WRITE_INTERVAL`] seconds and once all chunks have been tried.
+    async fn post_uploader_chunks(
+        &self,
+        uploader: &mut Uploader,
+        signed_transaction: Transaction,
+        chunks_buffer: usize,
+        log_dir: Option<PathBuf>,
+    ) -> Result<(), Error> {
+        let file_path = uploader.file_path.clone().ok_or(Error::MissingFilePath)?;
+        let mut stream = upload_transaction_chunks_from_file_stream(
+            self,
+            signed_transaction,
+            file_path,
+            uploader.missing_chunks(),
+            chunks_buffer,
+        );
+
+        let write_interval = Duration::from_secs(UPLOADER_WRITE_INTERVAL);
+        let mut last_write = Instant::now();
+        while let Some((i, result)) = stream.next().await {
+            uploader.last_request_time_end = Utc::now().timestamp_millis() as u64;
+            match result {
+                Ok(_) => {
+                    uploader.posted_chunks.insert(i);
+                }
+                Err(e) => {
+                    uploader.total_errors += 1;
+                    uploader.last_response_error = e.to_string();
+                }
+            }
+            if let Some(log_dir) = &log_dir {
+                if last_write.elapsed() >= write_interval {
+                    self.write_uploader(uploader, log_dir.clone()).await?;
+                    last_write = Instant::now();
+                }
+            }
+        }
+        if let Some(log_dir) = log_dir {
+            self.write_uploader(uploader, log_dir).await?;
+        }
+
+        if uploader.is_complete() {
+            Ok(())
+        } else {
+            Err(Error::IncompleteUpload(
+                uploader.transaction.id.to_string(),
+                uploader.number_of_chunks - uploader.posted_chunks.len(),
+            ))
+        }
+    }
+
+    /// Gets deep hash, signs and sets signature and id.
+    ///
+    /// Transactions can only be signed with Arweave keys, so returns an error if the [`Signer`]
+    /// has a different signature type.
+    pub fn sign_transaction(&self, mut transaction: Transaction) -> Result<Transaction, Error> {
+        let signer = self.transaction_signer()?;
+        let signature_data = self.crypto.transaction_signature_data(&transaction)?;
+        let signature = signer.sign(&signature_data)?;
+        let id = self.crypto.hash_sha256(&signature)?;
+        transaction.signature = Base64(signature);
         transaction.id = Base64(id.to_vec());
         Ok(transaction)
     }
@@ -997,50 +2361,48 @@ impl Arweave {
     ) -> Result<(Transaction, SigResponse), Error> {
         let lamports = std::cmp::max(&transaction.reward / RATE, FLOOR);
 
-        let mut sol_tx = create_sol_transaction(solana_url.clone(), from_keypair, lamports).await?;
-        let mut resp = get_sol_ar_signature(
-            sol_ar_url.clone(),
-            transaction.to_deep_hash_item()?,
-            sol_tx.clone(),
-        )
+        let deep_hash_item = transaction.to_deep_hash_item()?;
+
+        // The payment is only created once, and only sent again if the SOL service didn't receive
+        // it, so that it can't be paid twice.
+        let resp = async {
+            let sol_tx = self
+                .retry_policy
+                .retry(|| {
+                    create_sol_transaction(&self.client, solana_url.clone(), from_keypair, lamports)
+                })
+                .await?;
+            self.retry_policy
+                .retry_if(
+                    || {
+                        get_sol_ar_signature(
+                            &self.client,
+                            sol_ar_url.clone(),
+                            deep_hash_item.clone(),
+                            sol_tx.clone(),
+                        )
+                    },
+                    retry::is_unsent,
+                )
+                .await
+        }
         .await;
 
-        let mut retries = 0;
-        while retries < CHUNKS_RETRIES {
-            match resp {
-                Ok(_) => {
-                    retries = CHUNKS_RETRIES;
-                }
-                Err(_) => {
-                    println!(
-                        "Retrying Solana transaction ({} of {})...",
-                        retries + 1,
-                        CHUNKS_RETRIES
-                    );
-                    retries += 1;
-                    sleep(Duration::from_millis(300)).await;
-                    sol_tx =
-                        create_sol_transaction(solana_url.clone(), from_keypair, lamports).await?;
-                    resp = get_sol_ar_signature(
-                        sol_ar_url.clone(),
-                        transaction.to_deep_hash_item()?,
-                        sol_tx.clone(),
-                    )
-                    .await;
-                }
+        match resp {
+            Ok(sig_response) => {
+                let sig_response_copy = sig_response.clone();
+                transaction.signature = sig_response.ar_tx_sig;
+                transaction.id = sig_response.ar_tx_id;
+                transaction.owner = sig_response.ar_tx_owner;
+                Ok((transaction, sig_response_copy))
             }
-        }
-        if let Ok(sig_response) = resp {
-            let sig_response_copy = sig_response.clone();
-            transaction.signature = sig_response.ar_tx_sig;
-            transaction.id = sig_response.ar_tx_id;
-            transaction.owner = sig_response.ar_tx_owner;
-            Ok((transaction, sig_response_copy))
-        } else {
-            println!(
-                "There was a problem with the Solana network. Please try again later or use AR."
-            );
-            Err(Error::SolanaNetworkError)
+            Err(e) if retry::is_retryable(&e) => {
+                println!(
+                    "There was a problem with the Solana network. Please try again later or use AR."
+                );
+                Err(Error::SolanaNetworkError)
+            }
+            Err(e) => Err(e),
         }
     }
 
@@ -1052,6 +2414,22 @@ impl Arweave {
         last_tx: Option<Base64>,
         price_terms: (u64, u64),
     ) -> Result<Status, Error> {
+        if self.dedupe {
+            let (hash, uploaded) = self
+                .find_uploaded_file(&file_path, self.transaction_signer()?)
+                .await?;
+            if let Some(uploaded) = uploaded {
+                let status = uploaded_file_status(file_path, uploaded);
+                if let Some(log_dir) = log_dir {
+                    self.write_status(status.clone(), log_dir, None).await?;
+                }
+                return Ok(status);
+            }
+            additional_tags
+                .get_or_insert_with(Vec::new)
+                .push(Tag::from_utf8_strs(FILE_HASH_TAG, &hash)?);
+        }
+
         let mut auto_content_tag = true;
         let mut status_content_type = mime_guess::mime::OCTET_STREAM.to_string();
 
@@ -1068,20 +2446,36 @@ impl Arweave {
             }
         }
 
-        let transaction = self
-            .create_transaction_from_file_path(
+        // Files too large for the `tx/` endpoint are streamed from disk instead of read into memory.
+        let (id, reward) = if fs::metadata(&file_path).await?.len() > MAX_TX_DATA {
+            let transaction = self
+                .create_transaction_from_file_path_streaming(
+                    file_path.clone(),
+                    additional_tags,
+                    last_tx,
+                    price_terms,
+                    auto_content_tag,
+                )
+                .await?;
+            let signed_transaction = self.sign_transaction(transaction)?;
+            self.post_transaction_chunks_from_file(
+                signed_transaction,
                 file_path.clone(),
-                additional_tags,
-                last_tx,
-                price_terms,
-                auto_content_tag,
+                100,
+                log_dir.clone(),
             )
-            .await?;
-        let signed_transaction = self.sign_transaction(transaction)?;
-        let (id, reward) = if signed_transaction.data.0.len() > MAX_TX_DATA as usize {
-            self.post_transaction_chunks(signed_transaction, 100)
-                .await?
+            .await?
         } else {
+            let transaction = self
+                .create_transaction_from_file_path(
+                    file_path.clone(),
+                    additional_tags,
+                    last_tx,
+                    price_terms,
+                    auto_content_tag,
+                )
+                .await?;
+            let signed_transaction = self.sign_transaction(transaction)?;
             self.post_transaction(&signed_transaction).await?
         };
 
@@ -1110,6 +2504,15 @@ impl Arweave {
         sol_ar_url: Url,
         from_keypair: &Keypair,
     ) -> Result<Status, Error> {
+        // Transactions signed by the SOL service aren't from the local wallet, so they can't be
+        // looked up and are only tagged. The CLI rejects `--dedupe` for these uploads.
+        if self.dedupe {
+            let hash = file_hash(&file_path).await?;
+            additional_tags
+                .get_or_insert_with(Vec::new)
+                .push(Tag::from_utf8_strs(FILE_HASH_TAG, &hash)?);
+        }
+
         let mut auto_content_tag = true;
         let mut status_content_type = mime_guess::mime::OCTET_STREAM.to_string();
 
@@ -1126,23 +2529,39 @@ impl Arweave {
             }
         }
 
-        let transaction = self
-            .create_transaction_from_file_path(
+        let stream_from_file = fs::metadata(&file_path).await?.len() > MAX_TX_DATA;
+        let transaction = if stream_from_file {
+            self.create_transaction_from_file_path_streaming(
                 file_path.clone(),
                 additional_tags,
                 last_tx,
                 price_terms,
                 auto_content_tag,
             )
-            .await?;
+            .await?
+        } else {
+            self.create_transaction_from_file_path(
+                file_path.clone(),
+                additional_tags,
+                last_tx,
+                price_terms,
+                auto_content_tag,
+            )
+            .await?
+        };
 
         let (signed_transaction, sig_response): (Transaction, SigResponse) = self
             .sign_transaction_with_sol(transaction, solana_url, sol_ar_url, from_keypair)
             .await?;
 
-        let (id, reward) = if signed_transaction.data.0.len() > MAX_TX_DATA as usize {
-            self.post_transaction_chunks(signed_transaction, 100)
-                .await?
+        let (id, reward) = if stream_from_file {
+            self.post_transaction_chunks_from_file(
+                signed_transaction,
+                file_path.clone(),
+                100,
+                log_dir.clone(),
+            )
+            .await?
         } else {
             self.post_transaction(&signed_transaction).await?
         };
@@ -1269,8 +2688,11 @@ impl Arweave {
 
     /// Gets status from network.
     pub async fn get_status(&self, id: &Base64) -> Result<Status, Error> {
-        let url = self.base_url.join(&format!("tx/{}/status", id))?;
-        let resp = reqwest::get(url).await?;
+        self.retry_policy.retry(|| self.get_status_once(id)).await
+    }
+
+    async fn get_status_once(&self, id: &Base64) -> Result<Status, Error> {
+        let resp = self.get_from_gateways(&format!("tx/{}/status", id)).await?;
         let mut status = Status {
             id: id.clone(),
             ..Status::default()
@@ -1351,7 +2773,37 @@ impl Arweave {
         Ok(output)
     }
 
-    // Reads a status from file.
+    /// Reads the [`Uploader`] state file for transaction `id` from `log_dir`.
+    pub async fn read_uploader(&self, id: &Base64, log_dir: PathBuf) -> Result<Uploader, Error> {
+        let uploader_path = log_dir
+            .join(format!("uploader_{}", id))
+            .with_extension("json");
+
+        if uploader_path.exists() {
+            let data = fs::read_to_string(uploader_path).await?;
+            let uploader: Uploader = serde_json::from_str(&data)?;
+            Ok(uploader)
+        } else {
+            Err(Error::UploaderNotFound)
+        }
+    }
+
+    /// Writes [`Uploader`] state to `log_dir`, named with the transaction id.
+    pub async fn write_uploader(&self, uploader: &Uploader, log_dir: PathBuf) -> Result<(), Error> {
+        if uploader.transaction.id.0.is_empty() {
+            return Err(error::Error::UnsignedTransaction);
+        }
+
+        fs::write(
+            log_dir
+                .join(format!("uploader_{}", uploader.transaction.id))
+                .with_extension("json"),
+            serde_json::to_string(uploader)?,
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn read_status(&self, file_path: PathBuf, log_dir: PathBuf) -> Result<Status, Error> {
         let file_path_hash = blake3::hash(file_path.to_str().unwrap().as_bytes());
 
@@ -1383,6 +2835,10 @@ impl Arweave {
     pub async fn update_bundle_status(&self, file_path: PathBuf) -> Result<BundleStatus, Error> {
         let data = fs::read_to_string(&file_path).await?;
         let mut status: BundleStatus = serde_json::from_str(&data)?;
+        // No bundle was posted for files found already uploaded.
+        if status.deduplicated {
+            return Ok(status);
+        }
         let trans_status = self.get_status(&status.id).await?;
         status.last_modified = Utc::now();
         status.status = trans_status.status;
@@ -1794,15 +3250,24 @@ impl Arweave {
 #[cfg(test)]
 mod tests {
     use crate::{
+        ar_to_winstons,
+        bundle::{BundleWriter, DataItem, DataItemOptions, MAX_BUNDLE_DEPTH},
         error::Error,
-        transaction::{Base64, FromUtf8Strs, Tag},
+        signer::{EthereumSigner, SIGNATURE_TYPE_ED25519, SIGNATURE_TYPE_ETHEREUM},
+        test_utils::{serve, serve_with},
+        transaction::{Base64, FromUtf8Strs, Tag, Transaction, Uploader},
         utils::TempDir,
-        Arweave, BytesPrice, Status, LAMPORTS_PER_SOL, WINSTONS_PER_AR,
+        Arweave, BytesPrice, PathsChunk, Status, ToItems, LAMPORTS_PER_SOL, WINSTONS_PER_AR,
     };
     use futures::future::try_join_all;
     use glob::glob;
     use matches::assert_matches;
-    use std::{path::PathBuf, str::FromStr, time::Instant};
+    use solana_sdk::signer::keypair::Keypair;
+    use std::{
+        path::{Path, PathBuf},
+        str::FromStr,
+        time::Instant,
+    };
     use tokio::fs;
     use url::Url;
 
@@ -1845,6 +3310,401 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ar_to_winstons() -> Result<(), Error> {
+        assert_eq!(ar_to_winstons("1")?, WINSTONS_PER_AR);
+        assert_eq!(ar_to_winstons("0.5")?, WINSTONS_PER_AR / 2);
+        assert_eq!(ar_to_winstons(".000000000001")?, 1);
+        assert_eq!(ar_to_winstons("12.25")?, 12_250_000_000_000);
+        for invalid in ["", ".", "-1", "1e3", "0.0000000000001", "1.2.3", "99999999"] {
+            assert_matches!(ar_to_winstons(invalid), Err(Error::InvalidArAmount(_)));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_transfer_transaction() -> Result<(), Error> {
+        let arweave = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+
+        let target = Base64::from_str("7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg")?;
+        let last_tx = Base64::from_str("LCwsLCwsLA")?;
+        let transaction = arweave
+            .create_transfer_transaction(
+                target.clone(),
+                WINSTONS_PER_AR,
+                Vec::new(),
+                None,
+                Some(last_tx.clone()),
+                Some(1_000),
+            )
+            .await?;
+        assert_eq!(transaction.target, target);
+        assert_eq!(transaction.quantity, WINSTONS_PER_AR);
+        assert_eq!(transaction.reward, 1_000);
+        assert_eq!(transaction.data_size, 0);
+        assert!(transaction.data_root.0.is_empty());
+
+        let signed_transaction = arweave.sign_transaction(transaction)?;
+        let deep_hash = arweave
+            .crypto
+            .deep_hash(signed_transaction.to_deep_hash_item()?)?;
+        arweave
+            .crypto
+            .verify(&signed_transaction.signature.0, &deep_hash)?;
+
+        let transaction = arweave
+            .create_transfer_transaction(
+                target,
+                1,
+                b"memo".to_vec(),
+                None,
+                Some(last_tx),
+                Some(1_000),
+            )
+            .await?;
+        assert_eq!((transaction.data_size, transaction.reward), (4, 1_000));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_from_file_path_streaming() -> Result<(), Error> {
+        let arweave = Arweave::default();
+        let file_path = PathBuf::from("tests/fixtures/rebar3");
+        let last_tx = Base64::from_str("LCwsLCwsLA")?;
+
+        let transaction = arweave
+            .create_transaction_from_file_path(
+                file_path.clone(),
+                None,
+                Some(last_tx.clone()),
+                (0, 0),
+                true,
+            )
+            .await?;
+        let streamed = arweave
+            .create_transaction_from_file_path_streaming(
+                file_path.clone(),
+                None,
+                Some(last_tx),
+                (0, 0),
+                true,
+            )
+            .await?;
+
+        assert_eq!(streamed.data_root, transaction.data_root);
+        assert_eq!(streamed.data_size, transaction.data_size);
+        assert_eq!(streamed.tags, transaction.tags);
+        assert!(streamed.data.0.is_empty());
+        for i in 0..transaction.chunks.len() {
+            assert_eq!(
+                streamed.get_chunk_from_file(i, &file_path).await?,
+                transaction.get_chunk(i)?
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_format_1_transaction() -> Result<(), Error> {
+        let arweave = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+
+        let other_tags = vec![Tag::<Base64>::from_utf8_strs("key2", "value2")?];
+        let transaction = arweave
+            .create_format_1_transaction(
+                b"hello".to_vec(),
+                Some(other_tags),
+                Some(Base64::from_str("LCwsLCwsLA")?),
+                (1_000, 0),
+                false,
+            )
+            .await?;
+        assert_eq!(transaction.format, 1);
+        assert_eq!(transaction.data_size, 5);
+        assert!(transaction.data_root.0.is_empty());
+        assert!(transaction.chunks.is_empty());
+
+        let signature_data = transaction.format_1_signature_data();
+        assert!(signature_data.starts_with(&transaction.owner.0));
+        assert!(signature_data.ends_with(b"key2value2"));
+
+        let signed_transaction = arweave.sign_transaction(transaction)?;
+        let json = serde_json::to_string(&signed_transaction)?;
+        assert!(json.contains(r#""data":"aGVsbG8""#));
+
+        let mut transaction: Transaction = serde_json::from_str(&json)?;
+        assert_eq!(
+            transaction.id.0,
+            arweave.crypto.hash_sha256(&transaction.signature.0)?
+        );
+        arweave.crypto.verify_with_owner(
+            &transaction.owner.0,
+            &transaction.signature.0,
+            &arweave.crypto.transaction_signature_data(&transaction)?,
+        )?;
+
+        transaction.data = Base64(b"jello".to_vec());
+        assert_matches!(
+            arweave.crypto.verify_with_owner(
+                &transaction.owner.0,
+                &transaction.signature.0,
+                &arweave.crypto.transaction_signature_data(&transaction)?,
+            ),
+            Err(Error::RingUnspecified(_))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_format_1_transaction() -> Result<(), Error> {
+        let arweave = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+        let transaction = arweave
+            .create_format_1_transaction(
+                b"hello".to_vec(),
+                None,
+                Some(Base64::from_str("LCwsLCwsLA")?),
+                (1_000, 0),
+                false,
+            )
+            .await?;
+        let signed_transaction = arweave.sign_transaction(transaction)?;
+
+        // Stand-in gateway serving the transaction, then with its data changed, then with its
+        // data size changed.
+        let json = serde_json::to_value(&signed_transaction)?;
+        let mut tampered = json.clone();
+        tampered["data"] = Base64(b"jello".to_vec()).to_string().into();
+        let mut resized = json.clone();
+        resized["data_size"] = "6".into();
+        let (url, server) = serve(
+            [json, tampered, resized]
+                .iter()
+                .map(|t| ("200 OK", t.to_string()))
+                .collect(),
+        );
+        let arweave = Arweave {
+            base_url: url,
+            ..Arweave::default()
+        };
+
+        let mut data = Vec::new();
+        let id = &signed_transaction.id;
+        assert_eq!(arweave.download_transaction_data(id, &mut data).await?, 5);
+        assert_eq!(data, b"hello");
+        assert_matches!(
+            arweave.download_transaction_data(id, &mut Vec::new()).await,
+            Err(Error::InvalidSignature)
+        );
+        assert_matches!(
+            arweave.download_transaction_data(id, &mut Vec::new()).await,
+            Err(Error::DataSizeMismatch)
+        );
+        assert_eq!(server.join().unwrap()[0].target, format!("/tx/{}", id));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_transaction() -> Result<(), Error> {
+        let arweave = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+
+        let last_tx = Base64::from_str("LCwsLCwsLA")?;
+        let transaction = arweave
+            .create_transaction(
+                b"hello".to_vec(),
+                None,
+                Some(last_tx.clone()),
+                (1_000, 0),
+                true,
+            )
+            .await?;
+        assert_matches!(transaction.verify(), Err(Error::UnsignedTransaction));
+
+        let format_1_transaction = arweave
+            .create_format_1_transaction(b"hello".to_vec(), None, Some(last_tx), (1_000, 0), true)
+            .await?;
+
+        for transaction in [transaction, format_1_transaction] {
+            let signed_transaction = arweave.sign_transaction(transaction)?;
+            let mut transaction: Transaction =
+                serde_json::from_str(&serde_json::to_string(&signed_transaction)?)?;
+            transaction.verify()?;
+
+            transaction.reward += 1;
+            assert_matches!(transaction.verify(), Err(Error::InvalidSignature));
+            transaction.reward -= 1;
+
+            transaction.id = Base64(vec![0; 32]);
+            assert_matches!(transaction.verify(), Err(Error::TransactionIdMismatch));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_post_signed_transaction_from_file() -> Result<(), Error> {
+        let arweave = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+
+        let file_path = PathBuf::from("tests/fixtures/0.png");
+        let mut transaction = Arweave::default()
+            .create_transaction_from_file_path_streaming(
+                file_path.clone(),
+                None,
+                Some(Base64::from_str("LCwsLCwsLA")?),
+                (0, 0),
+                true,
+            )
+            .await?;
+        transaction.owner = Base64::default();
+
+        let unsigned_json = serde_json::to_string(&transaction)?;
+        let mut transaction: Transaction = serde_json::from_str(&unsigned_json)?;
+        assert_matches!(
+            arweave
+                .post_signed_transaction_from_file(
+                    transaction.clone_with_no_data()?,
+                    file_path,
+                    5,
+                    None
+                )
+                .await,
+            Err(Error::UnsignedTransaction)
+        );
+
+        transaction.owner = arweave.transaction_signer()?.owner()?;
+        let signed_transaction = arweave.sign_transaction(transaction)?;
+        let signed_json = serde_json::to_string(&signed_transaction)?;
+        let signed_transaction: Transaction = serde_json::from_str(&signed_json)?;
+        let deep_hash = arweave
+            .crypto
+            .deep_hash(signed_transaction.to_deep_hash_item()?)?;
+        arweave
+            .crypto
+            .verify(&signed_transaction.signature.0, &deep_hash)?;
+
+        assert_matches!(
+            arweave
+                .post_signed_transaction_from_file(
+                    signed_transaction,
+                    PathBuf::from("tests/fixtures/1.png"),
+                    5,
+                    None
+                )
+                .await,
+            Err(Error::DataRootMismatch)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_bundle_from_file_paths() -> Result<(), Error> {
+        let arweave = Arweave::default();
+        let temp_dir = TempDir::from_str("./tests/").await?;
+        let bundle_path = temp_dir.0.join("bundle");
+        let file_paths: Vec<PathBuf> = (0..5)
+            .map(|i| PathBuf::from(format!("tests/fixtures/{}.png", i)))
+            .collect();
+
+        let manifest = arweave
+            .write_bundle_from_file_paths(file_paths.clone(), Vec::new(), bundle_path.clone())
+            .await?;
+        let bundle = fs::read(&bundle_path).await?;
+        let data_items = arweave.deserialize_bundle(bundle)?;
+        assert_eq!(data_items.len(), 5);
+        for (data_item, file_path) in data_items.iter().zip(file_paths) {
+            assert_eq!(data_item.data.0, fs::read(&file_path).await?);
+            assert_eq!(
+                manifest["paths"][file_path.to_str().unwrap()]["id"],
+                data_item.id.to_string()
+            );
+        }
+
+        // The number of data items has to match the space reserved for headers.
+        let writer = BundleWriter::create(bundle_path.clone(), 1).await?;
+        assert_matches!(writer.finish().await, Err(Error::BundleItemCount(0, 1)));
+        let mut writer = BundleWriter::create(bundle_path, 1).await?;
+        writer.add_data_item(&data_items[0]).await?;
+        assert_matches!(
+            writer.add_data_item(&data_items[1]).await,
+            Err(Error::BundleItemCount(2, 1))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_read_resume_uploader() -> Result<(), Error> {
+        let arweave = Arweave::default();
+        let file_path = PathBuf::from("tests/fixtures/rebar3");
+        let transaction = arweave
+            .create_transaction_from_file_path_streaming(
+                file_path.clone(),
+                None,
+                Some(Base64::from_str("LCwsLCwsLA")?),
+                (0, 0),
+                true,
+            )
+            .await?;
+        let signed_transaction = arweave.sign_transaction(transaction)?;
+        let id = signed_transaction.id.clone();
+
+        let uploader = Uploader {
+            tx_posted: true,
+            transaction: signed_transaction.clone_with_no_data()?,
+            file_path: Some(PathBuf::from("tests/fixtures/1mb.bin")),
+            number_of_chunks: signed_transaction.chunks.len(),
+            posted_chunks: [0].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(!uploader.is_complete());
+        assert_eq!(uploader.missing_chunks(), vec![1, 2, 3]);
+
+        let temp_log_dir = TempDir::from_str("./tests/").await?;
+        let log_dir = temp_log_dir.0.clone();
+        arweave.write_uploader(&uploader, log_dir.clone()).await?;
+        assert_eq!(arweave.read_uploader(&id, log_dir.clone()).await?, uploader);
+
+        // Fails before posting anything if the file no longer matches the transaction.
+        let error = arweave
+            .resume_upload(&id, log_dir.clone(), 1)
+            .await
+            .unwrap_err();
+        assert_matches!(error, Error::DataRootMismatch);
+
+        let error = arweave
+            .read_uploader(&Base64(vec![0; 32]), log_dir)
+            .await
+            .unwrap_err();
+        assert_matches!(error, Error::UploaderNotFound);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_write_read_status() -> Result<(), Error> {
         let arweave = Arweave::from_keypair_path(
@@ -1948,6 +3808,449 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_verify_bundle() -> Result<(), Error> {
+        // Bundle signed by a wallet other than the one verifying it.
+        let signer = Arweave::from_keypair_path(
+            PathBuf::from(
+                "tests/fixtures/arweave-key-7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg.json",
+            ),
+            Url::from_str("http://url.com").unwrap(),
+        )
+        .await?;
+        let arweave = Arweave::default();
+
+        let data_items = signed_data_items(&signer, &["0.png", "1.png"], Path::new(""))?;
+        let (bundle, _) = signer.create_bundle_from_data_items(data_items)?;
+
+        let reports = arweave.verify_bundle(&bundle)?;
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|r| r.is_valid()));
+        assert_eq!(reports[0].owner, signer.crypto.wallet_address()?);
+        assert_eq!(reports[0].content_type, "image/png");
+        assert_eq!(arweave.deserialize_bundle(bundle.clone())?.len(), 2);
+
+        // Flip the last byte of data of the last item.
+        let mut tampered = bundle.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        let reports = arweave.verify_bundle(&tampered)?;
+        assert!(reports[0].is_valid());
+        assert!(!reports[1].valid_signature);
+        assert!(reports[1].valid_id);
+        assert_eq!(
+            arweave.verify_bundle_from_reader(std::io::Cursor::new(&tampered))?,
+            reports
+        );
+        assert_matches!(
+            arweave.deserialize_bundle(tampered),
+            Err(Error::InvalidSignature)
+        );
+
+        // Truncated bundles are reported as errors rather than panicking.
+        let truncated = bundle[..bundle.len() - 10].to_vec();
+        assert_matches!(
+            arweave.verify_bundle(&truncated),
+            Err(Error::InvalidDataItem)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_data_item_signature_types() -> Result<(), Error> {
+        let url = Url::from_str("http://url.com").unwrap();
+        let signers = vec![
+            (
+                Arweave {
+                    base_url: url.clone(),
+                    data_item_signer: Some(Box::new(Keypair::new())),
+                    ..Arweave::default()
+                },
+                SIGNATURE_TYPE_ED25519,
+                (64, 32),
+            ),
+            (
+                Arweave {
+                    base_url: url.clone(),
+                    data_item_signer: Some(Box::new(EthereumSigner::from_hex(
+                        "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+                    )?)),
+                    ..Arweave::default()
+                },
+                SIGNATURE_TYPE_ETHEREUM,
+                (65, 65),
+            ),
+        ];
+        let arweave = Arweave::default();
+
+        for (signer, signature_type, (signature_len, owner_len)) in signers {
+            let data_item = signer.create_data_item(b"hello, world".to_vec(), Vec::new(), true)?;
+            let data_item = signer.sign_data_item(data_item)?;
+            assert_eq!(data_item.signature_type, signature_type);
+            assert_eq!(data_item.signature.0.len(), signature_len);
+            assert_eq!(data_item.owner.0.len(), owner_len);
+
+            // Transactions are still signed with the Arweave keypair.
+            assert_eq!(
+                signer.transaction_signer()?.owner()?,
+                signer.crypto.keypair_modulus()?
+            );
+
+            let status = Status {
+                id: data_item.id.clone(),
+                file_path: Some(PathBuf::from("hello.txt")),
+                ..Status::default()
+            };
+            let (bundle, _) =
+                signer.create_bundle_from_data_items(vec![(data_item.clone(), status)])?;
+            assert!(arweave.verify_bundle(&bundle)?[0].is_valid());
+            assert_eq!(arweave.deserialize_bundle(bundle)?, vec![data_item]);
+        }
+
+        // Transactions aren't signed with the keypair in place of a signer that can't sign them.
+        let arweave = Arweave::from_signer(Box::new(Keypair::new()), url);
+        assert_matches!(
+            arweave.transaction_signer().map(|s| s.signature_type()),
+            Err(Error::UnsupportedSignatureType(SIGNATURE_TYPE_ED25519))
+        );
+        assert_matches!(
+            arweave.sign_transaction(Transaction::default()),
+            Err(Error::UnsupportedSignatureType(SIGNATURE_TYPE_ED25519))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_data_item_to_bundler() -> Result<(), Error> {
+        // Stand-in bundler that responds with a receipt for the data item it was sent.
+        let (bundler_url, server) = serve_with(1, |request| {
+            let data_item = DataItem::deserialize(request.body.clone()).unwrap();
+            let id = Base64(
+                Arweave::default()
+                    .crypto
+                    .hash_sha256(&data_item.signature.0)
+                    .unwrap()
+                    .to_vec(),
+            );
+            let receipt = format!(
+                r#"{{"id":"{}","timestamp":1652000000000,"block":930000}}"#,
+                id
+            );
+            ("200 OK", receipt)
+        });
+
+        let arweave = Arweave::default();
+        let temp_log_dir = TempDir::from_str("./tests/").await?;
+        let log_dir = temp_log_dir.0.clone();
+        let status = arweave
+            .upload_file_to_bundler(
+                PathBuf::from("tests/fixtures/0.png"),
+                Vec::new(),
+                &bundler_url,
+                Some(log_dir.clone()),
+            )
+            .await?;
+
+        let request = server.join().unwrap().remove(0);
+        assert_eq!(
+            (request.method.as_str(), request.target.as_str()),
+            ("POST", "/tx")
+        );
+        assert_eq!(
+            DataItem::deserialize(request.body)?.data.0,
+            std::fs::read("tests/fixtures/0.png")?
+        );
+
+        let receipt = status.bundler_receipt.clone().unwrap();
+        assert_eq!(receipt.id, status.id);
+        assert_eq!(receipt.block, Some(930000));
+        let read_status = arweave
+            .read_status(PathBuf::from("tests/fixtures/0.png"), log_dir)
+            .await?;
+        assert_eq!(read_status.bundler_receipt, Some(receipt));
+        Ok(())
+    }
+
+    /// Returns signed data items of the fixtures named `files`, with statuses for their file
+    /// names joined to `dir`.
+    fn signed_data_items(
+        arweave: &Arweave,
+        files: &[&str],
+        dir: &Path,
+    ) -> Result<Vec<(DataItem, Status)>, Error> {
+        files
+            .iter()
+            .map(|f| {
+                let data_item = arweave.create_data_item(
+                    std::fs::read(PathBuf::from("tests/fixtures").join(f))?,
+                    Vec::new(),
+                    true,
+                )?;
+                let data_item = arweave.sign_data_item(data_item)?;
+                let status = Status {
+                    id: data_item.id.clone(),
+                    file_path: Some(dir.join(f)),
+                    ..Status::default()
+                };
+                Ok((data_item, status))
+            })
+            .collect()
+    }
+
+    /// Returns a page of query results with the file uploaded as `id`.
+    fn found_file_page(id: &str) -> serde_json::Value {
+        serde_json::json!({ "data": { "transactions": {
+            "pageInfo": { "hasNextPage": false },
+            "edges": [{
+                "cursor": "c0",
+                "node": {
+                    "id": id,
+                    "owner": { "address": "7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg" },
+                    "recipient": "",
+                    "tags": [],
+                    "data": { "size": "1024", "type": "image/png" },
+                    "fee": { "winston": "0" },
+                    "quantity": { "winston": "0" },
+                    "block": { "height": 930000, "timestamp": 1652000000 },
+                    "bundledIn": { "id": "I-D4AkMq4rs4Sut0BHSHNCL0jS9vA8vXbi6jwzhjfv4" }
+                }
+            }]
+        }}})
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_upload_to_bundler() -> Result<(), Error> {
+        use serde_json::{json, Value};
+
+        let existing_id = "ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc";
+        let found = found_file_page(existing_id);
+        let not_found = json!({ "data": { "transactions": {
+            "pageInfo": { "hasNextPage": false },
+            "edges": []
+        }}});
+        let receipt = json!({ "id": existing_id, "timestamp": 1652000000000u64 });
+
+        // Stand-in gateway and bundler that finds the file the first time it is looked up, but
+        // not the second, when the file then gets posted to the bundler.
+        let (url, server) = serve(
+            [found, not_found, receipt]
+                .iter()
+                .map(|r| ("200 OK", r.to_string()))
+                .collect(),
+        );
+
+        let arweave = Arweave {
+            base_url: url.clone(),
+            dedupe: true,
+            ..Arweave::default()
+        };
+        let file_path = PathBuf::from("tests/fixtures/0.png");
+        let hash = crate::file_hash(&file_path).await?;
+        assert_eq!(hash, crate::data_hash(&std::fs::read(&file_path)?));
+
+        let status = arweave
+            .upload_file_to_bundler(file_path.clone(), Vec::new(), &url, None)
+            .await?;
+        assert_eq!(status.id.to_string(), existing_id);
+        assert_eq!(status.content_type, "image/png");
+        assert_eq!(status.bundler_receipt, None);
+
+        arweave
+            .upload_file_to_bundler(file_path, Vec::new(), &url, None)
+            .await?;
+        let requests = server.join().unwrap();
+
+        let variables = serde_json::from_slice::<Value>(&requests[0].body)?["variables"].clone();
+        assert_eq!(requests[0].target, "/graphql");
+        assert_eq!(
+            variables["owners"],
+            json!([arweave.signer().wallet_address()?.to_string()])
+        );
+        assert_eq!(
+            variables["tags"],
+            json!([{ "name": crate::FILE_HASH_TAG, "values": [hash] }])
+        );
+        assert_eq!(requests[2].target, "/tx");
+        let data_item = DataItem::deserialize(requests[2].body.clone())?;
+        assert!(data_item
+            .tags
+            .iter()
+            .any(|t| t.name == crate::FILE_HASH_TAG && t.value == hash));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_bundle() -> Result<(), Error> {
+        let existing_id = "ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc";
+        let (url, server) = serve(vec![("200 OK", found_file_page(existing_id).to_string())]);
+        let arweave = Arweave {
+            base_url: url,
+            dedupe: true,
+            ..Arweave::default()
+        };
+        let file_path = PathBuf::from("tests/fixtures/0.png");
+
+        // No bundle is posted for a chunk of files all already uploaded.
+        let status = arweave
+            .post_bundle_transaction_from_file_paths(
+                PathsChunk(vec![file_path.clone()], 1024),
+                Vec::new(),
+                (0, 0),
+                1,
+            )
+            .await?;
+        server.join().unwrap();
+        assert!(status.deduplicated);
+        assert_eq!(status.number_of_files, 0);
+        assert_eq!(
+            status.file_paths[file_path.to_str().unwrap()]["id"],
+            existing_id
+        );
+
+        // Nor is its status updated from the network.
+        let temp_log_dir = TempDir::from_str("./tests/").await?;
+        let status_path = temp_log_dir.0.join(existing_id).with_extension("json");
+        fs::write(&status_path, serde_json::to_string(&status)?).await?;
+        let updated = arweave.update_bundle_status(status_path).await?;
+        assert_eq!(updated.status, status.status);
+        assert!(updated.deduplicated);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_data_item_options() -> Result<(), Error> {
+        let target = Base64(vec![1; 32]);
+        let anchor = Base64(vec![2; 32]);
+        let mut arweave = Arweave::default();
+        arweave.data_item_options = DataItemOptions::default()
+            .target(target.clone())?
+            .anchor(anchor.clone())?;
+
+        let data_item = arweave.create_data_item(b"hello, world".to_vec(), Vec::new(), true)?;
+        let data_item = arweave.sign_data_item(data_item)?;
+        assert_eq!(data_item.target, target);
+        assert_eq!(data_item.anchor, anchor);
+        let mut deserialized = DataItem::deserialize(data_item.serialize()?)?;
+        deserialized.id = data_item.id.clone();
+        assert_eq!(deserialized, data_item);
+        assert_eq!(arweave.verify_data_item(&deserialized), (true, true));
+
+        // Identical data gets different anchors.
+        arweave.data_item_options = arweave.data_item_options.random_anchor();
+        let anchors = (0..2)
+            .map(|_| {
+                let data_item = arweave.create_data_item(b"hello".to_vec(), Vec::new(), true)?;
+                Ok(data_item.anchor)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        assert_eq!(anchors[0].0.len(), 32);
+        assert_ne!(anchors[0], anchors[1]);
+
+        assert_matches!(
+            DataItemOptions::default().target(Base64(vec![1; 31])),
+            Err(Error::InvalidDataItemOption(_))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nested_bundles() -> Result<(), Error> {
+        let arweave = Arweave::default();
+
+        // One nested bundle per collection, bundled again with a loose file.
+        let mut data_items = Vec::new();
+        for (collection, file_names) in [("a", ["0.png", "1.png"]), ("b", ["2.png", "3.png"])] {
+            let (bundle, _) = arweave.create_bundle_from_data_items(signed_data_items(
+                &arweave,
+                &file_names,
+                Path::new(""),
+            )?)?;
+            let tags = vec![Tag::<String>::from_utf8_strs("Collection", collection)?];
+            let data_item = arweave.create_data_item_from_bundle(bundle, tags)?;
+            assert!(data_item.is_bundle());
+            let status = Status {
+                id: data_item.id.clone(),
+                file_path: Some(PathBuf::from(collection)),
+                ..Status::default()
+            };
+            data_items.push((data_item, status));
+        }
+        data_items.extend(signed_data_items(&arweave, &["4.png"], Path::new(""))?);
+        let (bundle, _) = arweave.create_bundle_from_data_items(data_items)?;
+
+        let deserialized = arweave.deserialize_bundle(bundle.clone())?;
+        assert_eq!(deserialized.len(), 7);
+        assert_eq!(
+            deserialized
+                .iter()
+                .map(|d| d.is_bundle())
+                .collect::<Vec<_>>(),
+            vec![true, false, false, true, false, false, false]
+        );
+        assert_eq!(
+            deserialized[4].data.0,
+            std::fs::read("tests/fixtures/2.png")?
+        );
+
+        let reports = arweave.verify_bundle(&bundle)?;
+        assert_eq!(reports.len(), 7);
+        assert!(reports.iter().all(|r| r.is_valid()));
+
+        // Bundles nested deeper than the limit aren't read.
+        let mut bundle = arweave
+            .create_bundle_from_data_items(signed_data_items(&arweave, &["0.png"], Path::new(""))?)?
+            .0;
+        for _ in 0..MAX_BUNDLE_DEPTH {
+            let data_item = arweave.create_data_item_from_bundle(bundle, Vec::new())?;
+            let status = Status {
+                id: data_item.id.clone(),
+                file_path: Some(PathBuf::from("nested")),
+                ..Status::default()
+            };
+            bundle = arweave
+                .create_bundle_from_data_items(vec![(data_item, status)])?
+                .0;
+        }
+        assert_matches!(
+            arweave.deserialize_bundle(bundle.clone()),
+            Err(Error::BundleTooDeep(MAX_BUNDLE_DEPTH))
+        );
+        let reports = arweave.verify_bundle(&bundle)?;
+        assert_eq!(reports.len(), MAX_BUNDLE_DEPTH);
+        assert!(reports[MAX_BUNDLE_DEPTH - 1].error.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extract_bundle() -> Result<(), Error> {
+        let arweave = Arweave::default();
+        let mut data_items = signed_data_items(&arweave, &["0.png", "1.png"], Path::new("images"))?;
+
+        let manifest = arweave.create_manifest(data_items.iter().map(|d| d.1.clone()).collect())?;
+        let manifest_item =
+            arweave.sign_data_item(arweave.create_data_item_from_manifest(manifest)?)?;
+        let manifest_id = manifest_item.id.clone();
+        data_items.push((
+            manifest_item,
+            Status {
+                id: manifest_id.clone(),
+                file_path: Some(PathBuf::from("manifest.json")),
+                ..Status::default()
+            },
+        ));
+        let (bundle, _) = arweave.create_bundle_from_data_items(data_items)?;
+
+        let temp_dir = TempDir::from_str("./tests/").await?;
+        let extracted = arweave.extract_bundle(bundle, temp_dir.0.clone()).await?;
+        assert_eq!(extracted.len(), 3);
+        assert_eq!(extracted[0].1, temp_dir.0.join("images/0.png"));
+        assert_eq!(
+            fs::read(&extracted[1].1).await?,
+            fs::read("tests/fixtures/1.png").await?
+        );
+        assert_eq!(extracted[2].1, temp_dir.0.join(manifest_id.to_string()));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_price_points() -> Result<(), Error> {
         let mut price = 0 as u64;
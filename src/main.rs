@@ -1,24 +1,48 @@
 use arloader::{
+    ar_to_winstons,
+    client::ClientOptions,
     commands::*,
+    crypto::Provider,
+    error::Error,
+    gateway::Gateways,
+    graphql::{SortOrder, TransactionsQuery},
+    rate_limit::RateLimiter,
+    signer::{EthereumSigner, ExternalSigner, SIGNATURE_TYPE_ARWEAVE},
     status::{OutputFormat, StatusCode},
     transaction::{Base64, FromUtf8Strs, Tag},
     Arweave,
 };
 use clap::{
     self, crate_description, crate_name, crate_version, value_t, App, AppSettings, Arg, ArgGroup,
-    SubCommand, Values,
+    ArgMatches, SubCommand, Values,
 };
-use std::{fmt::Display, path::PathBuf, str::FromStr};
+use solana_sdk::signer::keypair::read_keypair_file;
+use std::{fmt::Display, path::PathBuf, str::FromStr, time::Duration};
 use url::Url;
 #[tokio::main]
 async fn main() -> CommandResult {
     env_logger::init();
     let app_matches = get_app().get_matches();
-    let base_url = app_matches
-        .value_of("base_url")
-        .map(|s| Url::from_str(&s.add_trailing_slash()))
+    let mut base_urls = app_matches
+        .values_of("base_url")
         .unwrap()
-        .unwrap();
+        .map(|s| Url::from_str(&s.add_trailing_slash()).unwrap());
+    let base_url = base_urls.next().unwrap();
+    let gateways = Gateways::new(base_urls.collect());
+    let client_options = get_client_options(&app_matches)?;
+    let client = client_options.build()?;
+    let rate_limiter = RateLimiter::new(
+        value_t!(app_matches.value_of("max_rps"), f64).ok(),
+        value_t!(app_matches.value_of("max_bandwidth"), u64).ok(),
+    );
+    let arweave = Arweave {
+        base_url,
+        gateways,
+        client,
+        client_options,
+        rate_limiter,
+        ..Arweave::default()
+    };
     let output_format = app_matches
         .value_of("output_format")
         .map(get_output_format)
@@ -28,19 +52,19 @@ async fn main() -> CommandResult {
 
     match (sub_command, arg_matches) {
         ("balance", Some(sub_arg_matches)) => {
-            let arweave = if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path")
-            {
-                Arweave::from_keypair_path(PathBuf::from(ar_keypair_path.expand_tilde()), base_url)
-                    .await
-                    .unwrap()
-            } else {
-                Arweave::default()
-            };
+            let arweave = get_arweave(sub_arg_matches, arweave).await?;
             let wallet_address = sub_arg_matches
                 .value_of("wallet_address")
                 .map(|v| v.to_string());
             command_wallet_balance(&arweave, wallet_address).await
         }
+        ("download", Some(sub_arg_matches)) => {
+            let id = sub_arg_matches.value_of("id").unwrap();
+            let out = sub_arg_matches
+                .value_of("out")
+                .map(|s| PathBuf::from(s.expand_tilde()));
+            command_download(&arweave, id, out).await
+        }
         ("estimate", Some(sub_arg_matches)) => {
             let paths_iter = sub_arg_matches
                 .values_of("file_paths")
@@ -52,7 +76,7 @@ async fn main() -> CommandResult {
                 * 1_000_000.0) as u64;
             let no_bundle = sub_arg_matches.is_present("no_bundle");
             command_get_cost(
-                &Arweave::default(),
+                &arweave,
                 paths_iter,
                 reward_mult,
                 with_sol,
@@ -61,13 +85,28 @@ async fn main() -> CommandResult {
             )
             .await
         }
+        ("extract-bundle", Some(sub_arg_matches)) => {
+            let bundle = sub_arg_matches.value_of("bundle").unwrap();
+            let out_dir = PathBuf::from(
+                sub_arg_matches
+                    .value_of("out_dir")
+                    .unwrap()
+                    .expand_tilde()
+                    .add_trailing_slash(),
+            );
+            command_extract_bundle(&arweave, bundle, out_dir).await
+        }
         ("get-status", Some(sub_arg_matches)) => {
             let id = sub_arg_matches.value_of("id").unwrap();
-            command_get_status(&Arweave::default(), id, &output_format).await
+            command_get_status(&arweave, id, &output_format).await
         }
         ("get-transaction", Some(sub_arg_matches)) => {
             let id = sub_arg_matches.value_of("id").unwrap();
-            command_get_transaction(&Arweave::default(), id).await
+            command_get_transaction(&arweave, id).await
+        }
+        ("inspect-bundle", Some(sub_arg_matches)) => {
+            let bundle = sub_arg_matches.value_of("bundle").unwrap();
+            command_inspect_bundle(&arweave, bundle, &output_format).await
         }
         ("list-status", Some(sub_arg_matches)) => {
             let log_dir = &sub_arg_matches
@@ -87,7 +126,7 @@ async fn main() -> CommandResult {
             let max_confirms = value_t!(sub_arg_matches.value_of("max_confirms"), u64).ok();
             if no_bundle {
                 command_list_statuses(
-                    &Arweave::default(),
+                    &arweave,
                     paths_iter.unwrap(),
                     log_dir,
                     statuses,
@@ -97,7 +136,7 @@ async fn main() -> CommandResult {
                 .await
             } else {
                 command_list_bundle_statuses(
-                    &Arweave::default(),
+                    &arweave,
                     log_dir,
                     statuses,
                     max_confirms,
@@ -106,16 +145,26 @@ async fn main() -> CommandResult {
                 .await
             }
         }
-        ("pending", Some(_)) => command_get_pending_count(&Arweave::default()).await,
-        ("reupload", Some(sub_arg_matches)) => {
-            let arweave = if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path")
-            {
-                Arweave::from_keypair_path(PathBuf::from(ar_keypair_path.expand_tilde()), base_url)
-                    .await
+        ("pending", Some(_)) => command_get_pending_count(&arweave).await,
+        ("query", Some(sub_arg_matches)) => {
+            let query = get_transactions_query(sub_arg_matches);
+            let limit = value_t!(sub_arg_matches.value_of("limit"), usize).ok();
+            command_query(&arweave, query, limit, &output_format).await
+        }
+        ("resume", Some(sub_arg_matches)) => {
+            let id = sub_arg_matches.value_of("id").unwrap();
+            let log_dir = PathBuf::from(
+                &sub_arg_matches
+                    .value_of("log_dir")
                     .unwrap()
-            } else {
-                Arweave::default()
-            };
+                    .expand_tilde()
+                    .add_trailing_slash(),
+            );
+            let buffer = value_t!(sub_arg_matches.value_of("buffer"), usize).unwrap();
+            command_resume(&arweave, id, log_dir, buffer).await
+        }
+        ("reupload", Some(sub_arg_matches)) => {
+            let arweave = get_arweave(sub_arg_matches, arweave).await?;
             let paths_iter = sub_arg_matches
                 .values_of("file_paths")
                 .map(|v| v.into_iter().map(PathBuf::from))
@@ -184,12 +233,45 @@ async fn main() -> CommandResult {
             let no_bundle = sub_arg_matches.is_present("no_bundle");
 
             if no_bundle {
-                command_status_report(&Arweave::default(), paths_iter.unwrap(), log_dir).await
+                command_status_report(&arweave, paths_iter.unwrap(), log_dir).await
             } else {
                 println!("Status report not implemented for bundles yet.");
                 Ok(())
             }
         }
+        ("transfer", Some(sub_arg_matches)) => {
+            let arweave = get_arweave(sub_arg_matches, arweave).await?;
+            let target = sub_arg_matches.value_of("address").unwrap();
+            let ar_amount = sub_arg_matches.value_of("ar_amount").unwrap();
+            command_transfer(&arweave, target, ar_amount).await
+        }
+        ("tx", Some(sub_arg_matches)) => match sub_arg_matches.subcommand() {
+            ("create", Some(create_arg_matches)) => {
+                let file_path = PathBuf::from(create_arg_matches.value_of("file_path").unwrap());
+                let out = PathBuf::from(create_arg_matches.value_of("out").unwrap().expand_tilde());
+                let tags = create_arg_matches.values_of("tags").map(get_tags_vec);
+                let reward_mult =
+                    value_t!(create_arg_matches.value_of("reward_multiplier"), f32).unwrap();
+                command_tx_create(&arweave, file_path, out, tags, reward_mult).await
+            }
+            ("post", Some(post_arg_matches)) => {
+                let tx_path = PathBuf::from(post_arg_matches.value_of("tx_path").unwrap());
+                let file_path = PathBuf::from(post_arg_matches.value_of("file_path").unwrap());
+                let log_dir = post_arg_matches
+                    .value_of("log_dir")
+                    .map(|s| s.expand_tilde().add_trailing_slash())
+                    .map(PathBuf::from);
+                let buffer = value_t!(post_arg_matches.value_of("buffer"), usize).unwrap();
+                command_tx_post(&arweave, tx_path, file_path, log_dir, buffer).await
+            }
+            ("sign", Some(sign_arg_matches)) => {
+                let arweave = get_arweave(sign_arg_matches, arweave).await?;
+                let tx_path = PathBuf::from(sign_arg_matches.value_of("tx_path").unwrap());
+                let out = PathBuf::from(sign_arg_matches.value_of("out").unwrap().expand_tilde());
+                command_tx_sign(&arweave, tx_path, out).await
+            }
+            _ => unreachable!(),
+        },
         ("update-metadata", Some(sub_arg_matches)) => {
             let paths_iter = sub_arg_matches
                 .values_of("file_paths")
@@ -204,7 +286,7 @@ async fn main() -> CommandResult {
             let update_image = sub_arg_matches.is_present("update_image");
             let update_animation_url = sub_arg_matches.is_present("update_animation_url");
             command_update_metadata(
-                &Arweave::default(),
+                &arweave,
                 paths_iter,
                 manifest_path,
                 link_file,
@@ -220,7 +302,7 @@ async fn main() -> CommandResult {
                 .expand_tilde()
                 .add_trailing_slash();
             let buffer = value_t!(sub_arg_matches.value_of("buffer"), usize).unwrap();
-            command_update_nft_statuses(&Arweave::default(), log_dir, &output_format, buffer).await
+            command_update_nft_statuses(&arweave, log_dir, &output_format, buffer).await
         }
         ("update-status", Some(sub_arg_matches)) => {
             let log_dir = PathBuf::from(
@@ -239,35 +321,16 @@ async fn main() -> CommandResult {
                         .values_of("file_paths")
                         .map(|v| v.into_iter().map(PathBuf::from))
                         .unwrap();
-                    command_update_statuses(
-                        &Arweave::default(),
-                        paths_iter,
-                        log_dir,
-                        &output_format,
-                        buffer,
-                    )
-                    .await
+                    command_update_statuses(&arweave, paths_iter, log_dir, &output_format, buffer)
+                        .await
                 }
                 false => {
-                    command_update_bundle_statuses(
-                        &Arweave::default(),
-                        log_dir,
-                        &output_format,
-                        buffer,
-                    )
-                    .await
+                    command_update_bundle_statuses(&arweave, log_dir, &output_format, buffer).await
                 }
             }
         }
         ("upload", Some(sub_arg_matches)) => {
-            let arweave = if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path")
-            {
-                Arweave::from_keypair_path(PathBuf::from(ar_keypair_path.expand_tilde()), base_url)
-                    .await
-                    .unwrap()
-            } else {
-                Arweave::default()
-            };
+            let mut arweave = get_arweave(sub_arg_matches, arweave).await?;
             let paths_iter = sub_arg_matches
                 .values_of("file_paths")
                 .map(|v| v.into_iter().map(PathBuf::from))
@@ -285,6 +348,45 @@ async fn main() -> CommandResult {
             let sol_keypair_path = sub_arg_matches
                 .value_of("sol_keypair_path")
                 .map(PathBuf::from);
+            if sub_arg_matches.is_present("sign_with_sol") {
+                let keypair = read_keypair_file(sol_keypair_path.clone().unwrap())?;
+                arweave.data_item_signer = Some(Box::new(keypair));
+            }
+            if let Some(eth_key_path) = sub_arg_matches.value_of("eth_key_path") {
+                let signer =
+                    EthereumSigner::from_key_path(PathBuf::from(eth_key_path.expand_tilde()))?;
+                arweave.data_item_signer = Some(Box::new(signer));
+            }
+
+            if let Some(target) = sub_arg_matches.value_of("data_item_target") {
+                arweave.data_item_options = arweave
+                    .data_item_options
+                    .target(Base64::from_str(target)?)?;
+            }
+            if sub_arg_matches.is_present("random_anchor") {
+                arweave.data_item_options = arweave.data_item_options.random_anchor();
+            }
+            arweave.dedupe = sub_arg_matches.is_present("dedupe");
+            if arweave.dedupe && with_sol && no_bundle {
+                clap::Error::with_description(
+                    "--dedupe can't be used with --with-sol and --no-bundle, since those \
+                    transactions are signed by the SOL service's wallet rather than yours",
+                    clap::ErrorKind::ArgumentConflict,
+                )
+                .exit();
+            }
+            if let Some(bundler_url) = sub_arg_matches.value_of("bundler_url") {
+                return command_upload_to_bundler(
+                    &arweave,
+                    paths_iter,
+                    log_dir,
+                    sub_arg_matches.values_of("tags").map(get_tags_vec),
+                    Url::from_str(&bundler_url.add_trailing_slash())?,
+                    &output_format,
+                    buffer,
+                )
+                .await;
+            }
 
             match (with_sol, no_bundle) {
                 (false, false) => {
@@ -342,14 +444,7 @@ async fn main() -> CommandResult {
             }
         }
         ("upload-nfts", Some(sub_arg_matches)) => {
-            let arweave = if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path")
-            {
-                Arweave::from_keypair_path(PathBuf::from(ar_keypair_path.expand_tilde()), base_url)
-                    .await
-                    .unwrap()
-            } else {
-                Arweave::default()
-            };
+            let arweave = get_arweave(sub_arg_matches, arweave).await?;
             let paths_iter = sub_arg_matches
                 .values_of("file_paths")
                 .map(|v| v.into_iter().map(PathBuf::from))
@@ -380,15 +475,38 @@ async fn main() -> CommandResult {
             )
             .await
         }
+        ("verify-tx", Some(sub_arg_matches)) => {
+            let id = sub_arg_matches.value_of("id").unwrap();
+            command_verify_transaction(&arweave, id).await
+        }
+        ("wallet", Some(sub_arg_matches)) => match sub_arg_matches.subcommand() {
+            ("encrypt", Some(encrypt_arg_matches)) => {
+                let keypair_path = encrypt_arg_matches
+                    .value_of("keypair_path")
+                    .map(|s| PathBuf::from(s.expand_tilde()))
+                    .unwrap();
+                let out = encrypt_arg_matches
+                    .value_of("out")
+                    .map(|s| PathBuf::from(s.expand_tilde()))
+                    .unwrap();
+                let passphrase = get_keypair_passphrase(true)?;
+                command_wallet_encrypt(keypair_path, out, passphrase).await
+            }
+            ("new", Some(new_arg_matches)) => {
+                let out = new_arg_matches
+                    .value_of("out")
+                    .map(|s| PathBuf::from(s.expand_tilde()));
+                let passphrase = if new_arg_matches.is_present("encrypt") {
+                    Some(get_keypair_passphrase(true)?)
+                } else {
+                    None
+                };
+                command_wallet_new(out, passphrase).await
+            }
+            _ => unreachable!(),
+        },
         ("upload-manifest", Some(sub_arg_matches)) => {
-            let arweave = if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path")
-            {
-                Arweave::from_keypair_path(PathBuf::from(ar_keypair_path.expand_tilde()), base_url)
-                    .await
-                    .unwrap()
-            } else {
-                Arweave::default()
-            };
+            let arweave = get_arweave(sub_arg_matches, arweave).await?;
             let log_dir = &sub_arg_matches
                 .value_of("log_dir")
                 .unwrap()
@@ -412,8 +530,7 @@ async fn main() -> CommandResult {
                 .map(PathBuf::from)
                 .unwrap();
             let link_file = sub_arg_matches.is_present("link_file");
-            command_write_metaplex_items(&Arweave::default(), paths_iter, manifest_path, link_file)
-                .await
+            command_write_metaplex_items(&arweave, paths_iter, manifest_path, link_file).await
         }
         _ => unreachable!(),
     }
@@ -429,9 +546,82 @@ fn get_app() -> App<'static, 'static> {
                 .long("base-url")
                 .value_name("AR_BASE_URL")
                 .validator(is_parsable::<Url>)
+                .multiple(true)
+                .number_of_values(1)
+                .use_delimiter(true)
                 .default_value("https://arweave.net/")
                 .env("AR_BASE_URL")
-                .help("Base url for network requests."),
+                .help(
+                    "Base url for network requests. Repeat to fail over to additional gateways \
+                    in order, or separate with commas in AR_BASE_URL.",
+                ),
+        )
+        .arg(
+            Arg::with_name("connect_timeout")
+                .long("connect-timeout")
+                .value_name("SECONDS")
+                .validator(is_parsable::<u64>)
+                .default_value("30")
+                .env("AR_CONNECT_TIMEOUT")
+                .help(
+                    "Seconds to wait for connections to be established. Set to 0 to wait \
+                    indefinitely.",
+                ),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .validator(is_parsable::<u64>)
+                .default_value("300")
+                .env("AR_TIMEOUT")
+                .help("Seconds to wait for requests to complete. Set to 0 to wait indefinitely."),
+        )
+        .arg(
+            Arg::with_name("proxy")
+                .long("proxy")
+                .value_name("PROXY_URL")
+                .validator(is_parsable::<Url>)
+                .env("AR_PROXY")
+                .help(
+                    "Send all requests through a proxy, including credentials in the url if \
+                    required. Defaults to HTTPS_PROXY or HTTP_PROXY if set.",
+                ),
+        )
+        .arg(
+            Arg::with_name("headers")
+                .long("header")
+                .value_name("NAME:VALUE")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(is_valid_header)
+                .help(
+                    "Add a header to requests to gateways and bundlers. Repeat to add more \
+                    than one.",
+                ),
+        )
+        .arg(
+            Arg::with_name("user_agent")
+                .long("user-agent")
+                .value_name("USER_AGENT")
+                .env("AR_USER_AGENT")
+                .help("Specify the User-Agent header sent with requests."),
+        )
+        .arg(
+            Arg::with_name("max_rps")
+                .long("max-rps")
+                .value_name("REQUESTS")
+                .validator(is_positive::<f64>)
+                .env("AR_MAX_RPS")
+                .help("Limit requests to gateways and bundlers to <REQUESTS> per second."),
+        )
+        .arg(
+            Arg::with_name("max_bandwidth")
+                .long("max-bandwidth")
+                .value_name("BYTES")
+                .validator(is_positive::<u64>)
+                .env("AR_MAX_BANDWIDTH")
+                .help("Limit data sent to gateways and bundlers to <BYTES> per second."),
         )
         .arg(
             Arg::with_name("output_format")
@@ -460,6 +650,18 @@ fn get_app() -> App<'static, 'static> {
                 )
                 .arg(ar_keypair_path_arg()),
         )
+        .subcommand(
+            SubCommand::with_name("download")
+                .about("Downloads transaction data, validating each chunk against the data root.")
+                .arg(id_arg())
+                .arg(
+                    Arg::with_name("out")
+                        .long("out")
+                        .value_name("OUT")
+                        .takes_value(true)
+                        .help("Specify the file path to write data to. Defaults to <ID>."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("estimate")
                 .about("Prints the estimated cost of uploading files.")
@@ -475,6 +677,23 @@ fn get_app() -> App<'static, 'static> {
                     " ,
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("extract-bundle")
+                .about("Writes the data of each item in a bundle to a directory.")
+                .arg(bundle_arg())
+                .arg(
+                    Arg::with_name("out_dir")
+                        .value_name("OUT_DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Specify the directory to write data to. Created if it doesn't exist."),
+                )
+                .after_help(
+                    "EXAMPLES:\nTo extract the items of bundle ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc to where/to/extract:\n\n\tarloader extract-bundle ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc where/to/extract \
+                    \n\nNOTES:\n- Files are named with their paths from a manifest included in the bundle, if there is one, or otherwise with their ids.
+                    ",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("get-status")
                 .about("Prints the status of a transaction.")
@@ -485,6 +704,16 @@ fn get_app() -> App<'static, 'static> {
                 .about("Gets a transaction from the network.")
                 .arg(id_arg()),
         )
+        .subcommand(
+            SubCommand::with_name("inspect-bundle")
+                .about("Prints the items in a bundle and whether they verify against their owners.")
+                .arg(bundle_arg())
+                .after_help(
+                    "EXAMPLES:\nTo list the items in bundle ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc with their tags:\n\n\tarloader inspect-bundle ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc \
+                    \n\nTo list the ids of items that fail verification in a bundle saved to where/my/bundle/at:\n\n\tarloader inspect-bundle where/my/bundle/at --output quiet
+                    ",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("list-status")
                 .about("Prints statuses.")
@@ -505,6 +734,96 @@ fn get_app() -> App<'static, 'static> {
         .subcommand(
             SubCommand::with_name("pending").about("Prints count of pending network transactions."),
         )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("Prints transactions matching the filters provided, most recent first.")
+                .arg(
+                    Arg::with_name("owners")
+                        .long("owner")
+                        .value_name("ADDRESS")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .validator(is_valid_address)
+                        .help(
+                            "Match transactions owned by <ADDRESS>. Repeat to match any of \
+                            several owners.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("tags")
+                        .long("tag")
+                        .value_name("NAME:VALUE")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .validator(is_valid_tag)
+                        .help(
+                            "Match transactions with tag <NAME> set to <VALUE>. Repeat to match \
+                            several tags, with transactions matching any of the values given for \
+                            the same <NAME>.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("ids")
+                        .long("id")
+                        .value_name("ID")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .validator(is_parsable::<Base64>)
+                        .help(
+                            "Match the transaction with <ID>. Repeat to match several \
+                            transactions.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("min_block")
+                        .long("min-block")
+                        .value_name("HEIGHT")
+                        .validator(is_parsable::<u64>)
+                        .help("Match transactions in blocks at or above <HEIGHT>."),
+                )
+                .arg(
+                    Arg::with_name("max_block")
+                        .long("max-block")
+                        .value_name("HEIGHT")
+                        .validator(is_parsable::<u64>)
+                        .help("Match transactions in blocks at or below <HEIGHT>."),
+                )
+                .group(
+                    ArgGroup::with_name("filters")
+                        .args(&["owners", "tags", "ids", "min_block", "max_block"])
+                        .multiple(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("NUMBER")
+                        .validator(is_positive::<usize>)
+                        .help("Print at most <NUMBER> transactions."),
+                )
+                .arg(
+                    Arg::with_name("ascending")
+                        .long("ascending")
+                        .help("Print the oldest transactions first."),
+                )
+                .after_help(
+                    "EXAMPLES:\nTo list everything uploaded by wallet 7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg:\n\n\tarloader query --owner 7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg \
+                    \n\nTo list the ids of the 10 most recent pngs uploaded by that wallet:\n\n\tarloader query --owner 7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg --tag Content-Type:image/png --limit 10 --output quiet \
+                    \n\nNOTES:\n- Transactions have to match every filter provided.\n- Results come from the GraphQL endpoint of <AR_BASE_URL>, which may not have indexed the most recent transactions yet.\n",
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("resume")
+                .about("Resumes a chunked upload that failed partway through.")
+                .arg(id_arg())
+                .arg(log_dir_arg_read().long("log-dir"))
+                .arg(buffer_arg("5"))
+                .after_help(
+                    "EXAMPLES:\nTo post the chunks still missing for transaction ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc uploaded from where/my/files/at with statuses written to where/my/files/at/status:\n\n\tarloader resume ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc --log-dir where/my/files/at/status \
+                    \n\nNOTES:\n- Upload progress is only recorded for files larger than 10 MB uploaded with `--no-bundle` and a <LOG_DIR>.\n- The file being uploaded must not have changed since the original upload.
+                    ",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("reupload")
                 .about("Reuploads files.")
@@ -515,15 +834,16 @@ fn get_app() -> App<'static, 'static> {
                 .arg(reward_multiplier_arg())
                 .arg(statuses_arg())
                 .arg(max_confirms_arg())
-                .arg(ar_keypair_path_arg().required_unless("with_sol"))
+                .arg(ar_keypair_path_arg().required_unless_one(&["with_sol", "ar_signer_command"]))
                 .arg(ar_default_keypair())
+                .arg(ar_signer_command_arg())
                 .arg(with_sol_arg().requires("sol_keypair_path"))
                 .arg(sol_keypair_path_arg())
                 .arg(buffer_arg("5"))
                 .arg(bundle_size_arg())
                 .group(
                     ArgGroup::with_name("ar_keypair")
-                        .args(&["ar_keypair_path", "ar_default_keypair"])
+                        .args(&["ar_keypair_path", "ar_default_keypair", "ar_signer_command"])
                         .required(true),
                 ).after_help(
                     "EXAMPLES:\nTo re-upload pngs previously uploaded from where/my/files/at in bundles with statuses written to where/my/files/at/status with a status of NotFound using an AR keypair with a path of path/to/my/ar_keypair.json:\n\n\tarloader reupload where/my/files/at/*.png --log-dir where/my/files/at/status --statuses NotFound --ar_keypair path path/to/my/ar_keypair.json\
@@ -545,6 +865,69 @@ fn get_app() -> App<'static, 'static> {
                     " ,
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("transfer")
+                .about("Transfers AR to another wallet.")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_address)
+                        .help("Specify the address of the wallet to transfer to."),
+                )
+                .arg(
+                    Arg::with_name("ar_amount")
+                        .value_name("AR_AMOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_ar_amount)
+                        .help("Specify the amount of AR to transfer, like 0.5."),
+                )
+                .arg(ar_keypair_path_arg().required_unless("ar_signer_command"))
+                .arg(ar_signer_command_arg())
+                .after_help(
+                    "EXAMPLES:\nTo transfer 0.5 AR to wallet 7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg from the wallet with its keypair at path/to/my/ar_keypair.json:\n\n\tarloader transfer 7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg 0.5 --ar-keypair-path path/to/my/ar_keypair.json \
+                    \n\nNOTES:\n- The transfer is only posted if the wallet balance covers both the amount and the reward.\n- Transfers to wallets that haven't been used before include an additional fee in the reward.
+                    ",
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tx")
+                .about("Creates, signs and posts transactions as separate steps, so that keypairs can be kept offline.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Writes an unsigned transaction for a file to json.")
+                        .arg(file_path_arg())
+                        .arg(out_arg().help("Specify the file path to write the unsigned transaction to."))
+                        .arg(tags_arg())
+                        .arg(reward_multiplier_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("post")
+                        .about("Posts a signed transaction and the chunks of the file it was created from.")
+                        .arg(tx_path_arg())
+                        .arg(file_path_arg())
+                        .arg(log_dir_arg_write().long("log-dir"))
+                        .arg(buffer_arg("5")),
+                )
+                .subcommand(
+                    SubCommand::with_name("sign")
+                        .about("Signs a transaction written by `tx create` without accessing the network.")
+                        .arg(tx_path_arg())
+                        .arg(out_arg().help("Specify the file path to write the signed transaction to."))
+                        .arg(ar_keypair_path_arg().required_unless("ar_signer_command"))
+                        .arg(ar_signer_command_arg()),
+                )
+                .after_help(
+                    "EXAMPLES:\nTo create an unsigned transaction for some/file.mp4 on a networked machine:\n\n\tarloader tx create some/file.mp4 --out unsigned.json \
+                    \n\nTo sign it on a machine with the keypair at path/to/my/ar_keypair.json:\n\n\tarloader tx sign unsigned.json --out signed.json --ar-keypair-path path/to/my/ar_keypair.json \
+                    \n\nTo post the signed transaction and the chunks of some/file.mp4 back on the networked machine:\n\n\tarloader tx post signed.json some/file.mp4 \
+                    \n\nNOTES:\n- Transactions expire if not posted within about 50 blocks of being created, as they are anchored with `tx_anchor`.\n- The file must not change between creating and posting the transaction.\n- Pass `--log-dir` to `tx post` to be able to finish interrupted uploads with `arloader resume`.
+                    ",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("update-nft-status")
                 .about("Updates statuses from NFT upload.")
@@ -582,15 +965,22 @@ fn get_app() -> App<'static, 'static> {
                 .arg(no_bundle_arg())
                 .arg(tags_arg())
                 .arg(reward_multiplier_arg())
-                .arg(ar_keypair_path_arg().required_unless("with_sol"))
+                .arg(ar_keypair_path_arg().required_unless_one(&["with_sol", "ar_signer_command"]))
                 .arg(ar_default_keypair())
+                .arg(ar_signer_command_arg())
                 .arg(with_sol_arg().requires("sol_keypair_path"))
                 .arg(sol_keypair_path_arg())
+                .arg(sign_with_sol_arg())
+                .arg(eth_key_path_arg())
+                .arg(bundler_url_arg())
+                .arg(data_item_target_arg())
+                .arg(random_anchor_arg())
+                .arg(dedupe_arg())
                 .arg(buffer_arg("5"))
                 .arg(bundle_size_arg())
                 .group(
                     ArgGroup::with_name("ar_keypair")
-                        .args(&["ar_keypair_path", "ar_default_keypair"])
+                        .args(&["ar_keypair_path", "ar_default_keypair", "ar_signer_command"])
                         .required(true),
                 ),
         )
@@ -599,13 +989,14 @@ fn get_app() -> App<'static, 'static> {
                 .about("Uploads a manifest for uploaded files. Only currently implemented bundles.")
                 .arg(log_dir_arg_read().required(true))
                 .arg(reward_multiplier_arg())
-                .arg(ar_keypair_path_arg().required_unless("with_sol"))
+                .arg(ar_keypair_path_arg().required_unless_one(&["with_sol", "ar_signer_command"]))
                 .arg(ar_default_keypair())
+                .arg(ar_signer_command_arg())
                 .arg(with_sol_arg().requires("sol_keypair_path"))
                 .arg(sol_keypair_path_arg())
                 .group(
                     ArgGroup::with_name("ar_keypair")
-                        .args(&["ar_keypair_path", "ar_default_keypair"])
+                        .args(&["ar_keypair_path", "ar_default_keypair", "ar_signer_command"])
                         .required(true),
                 ),
         )
@@ -616,8 +1007,9 @@ fn get_app() -> App<'static, 'static> {
                 .arg(log_dir_arg_write().long("log-dir"))
                 .arg(tags_arg())
                 .arg(reward_multiplier_arg())
-                .arg(ar_keypair_path_arg().required_unless("with_sol"))
+                .arg(ar_keypair_path_arg().required_unless_one(&["with_sol", "ar_signer_command"]))
                 .arg(ar_default_keypair())
+                .arg(ar_signer_command_arg())
                 .arg(with_sol_arg().requires("sol_keypair_path"))
                 .arg(sol_keypair_path_arg())
                 .arg(buffer_arg("5"))
@@ -625,10 +1017,71 @@ fn get_app() -> App<'static, 'static> {
                 .arg(link_file_arg())
                 .group(
                     ArgGroup::with_name("ar_keypair")
-                        .args(&["ar_keypair_path", "ar_default_keypair"])
+                        .args(&["ar_keypair_path", "ar_default_keypair", "ar_signer_command"])
                         .required(true),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("verify-tx")
+                .about("Verifies the signature of a transaction on the network.")
+                .arg(id_arg())
+                .after_help(
+                    "EXAMPLES:\nTo verify that transaction ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc was signed by its owner:\n\n\tarloader verify-tx ZvYb5H6L4Um5mWkAUHR3jMAuQxB-m31nDiVUtZhUFGc \
+                    \n\nNOTES:\n- Prints the address of the wallet that signed the transaction if valid, otherwise exits with an error.\n- Supports both format 1 and format 2 transactions.
+                    ",
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("wallet")
+                .about("Manages Arweave wallets.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("encrypt")
+                        .about("Encrypts a keypair file with a passphrase.")
+                        .arg(
+                            Arg::with_name("keypair_path")
+                                .value_name("KEYPAIR_PATH")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_valid_file_path)
+                                .help("Specify the path of the plaintext keypair file to encrypt."),
+                        )
+                        .arg(
+                            Arg::with_name("out")
+                                .long("out")
+                                .value_name("OUT")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Specify the file path to write the encrypted keypair to."),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("new")
+                        .about("Generates a new wallet, printing its address.")
+                        .arg(
+                            Arg::with_name("out")
+                                .long("out")
+                                .value_name("OUT")
+                                .takes_value(true)
+                                .help(
+                                    "Specify the file path to write the keypair to. \
+                                    Defaults to arweave-keyfile-<ADDRESS>.json.",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("encrypt")
+                                .long("encrypt")
+                                .takes_value(false)
+                                .help("Encrypts the keypair with a passphrase."),
+                        ),
+                )
+                .after_help(
+                    "EXAMPLES:\nTo create a new wallet with its keypair written to path/to/my/ar_keypair.json:\n\n\tarloader wallet new --out path/to/my/ar_keypair.json \
+                    \n\nTo encrypt the keypair at path/to/my/ar_keypair.json with a passphrase:\n\n\tarloader wallet encrypt path/to/my/ar_keypair.json --out path/to/my/ar_keypair.enc.json \
+                    \n\nNOTES:\n- Existing files are never overwritten.\n- Only the wallet address is printed to stdout, so it can be captured in scripts.\n- Passphrases are read from the AR_KEYPAIR_PASSPHRASE environment variable if set, or otherwise prompted for.\n- Encrypted keypair files can be used anywhere a keypair file path is accepted.
+                    ",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("write-metaplex-items")
                 .about("Writes metaplex items to file.")
@@ -644,6 +1097,77 @@ fn get_app() -> App<'static, 'static> {
     app_matches
 }
 
+/// Creates [`TransactionsQuery`] from the filters provided to the query command.
+fn get_transactions_query(sub_arg_matches: &ArgMatches<'_>) -> TransactionsQuery {
+    let mut query = TransactionsQuery::default();
+    for owner in sub_arg_matches.values_of("owners").into_iter().flatten() {
+        query = query.owner(Base64::from_str(owner).unwrap());
+    }
+    for tag in sub_arg_matches.values_of("tags").into_iter().flatten() {
+        let (name, value) = tag.split_once(':').unwrap();
+        query = query.tag(name, value);
+    }
+    for id in sub_arg_matches.values_of("ids").into_iter().flatten() {
+        query = query.id(Base64::from_str(id).unwrap());
+    }
+    let min_block = value_t!(sub_arg_matches.value_of("min_block"), u64).ok();
+    let max_block = value_t!(sub_arg_matches.value_of("max_block"), u64).ok();
+    if min_block.is_some() || max_block.is_some() {
+        query = query.block_range(min_block, max_block);
+    }
+    if sub_arg_matches.is_present("ascending") {
+        query = query.sort(SortOrder::HeightAsc);
+    }
+    query
+}
+
+/// Creates [`ClientOptions`] from the connection arguments.
+fn get_client_options(app_matches: &ArgMatches<'_>) -> Result<ClientOptions, Error> {
+    let seconds = |name| {
+        Some(value_t!(app_matches.value_of(name), u64).unwrap())
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs)
+    };
+    let mut options = ClientOptions::default()
+        .connect_timeout(seconds("connect_timeout"))
+        .timeout(seconds("timeout"));
+    if let Some(proxy) = app_matches.value_of("proxy") {
+        options = options.proxy(Url::from_str(proxy)?);
+    }
+    for header in app_matches.values_of("headers").into_iter().flatten() {
+        let (name, value) = header.split_once(':').unwrap();
+        options = options.header(name.trim(), value.trim())?;
+    }
+    if let Some(user_agent) = app_matches.value_of("user_agent") {
+        options = options.user_agent(user_agent);
+    }
+    Ok(options)
+}
+
+/// Returns `arweave`, which is already configured for the network, set to sign with the external
+/// signer command or keypair file provided, if any, or otherwise the default keypair.
+async fn get_arweave(sub_arg_matches: &ArgMatches<'_>, arweave: Arweave) -> Result<Arweave, Error> {
+    if let Some(command) = sub_arg_matches.value_of("ar_signer_command") {
+        let signer = ExternalSigner::from_command(command, SIGNATURE_TYPE_ARWEAVE)?;
+        Ok(Arweave {
+            signer: Some(Box::new(signer)),
+            ..arweave
+        })
+    } else if let Some(ar_keypair_path) = sub_arg_matches.value_of("ar_keypair_path") {
+        let keypair_path = PathBuf::from(ar_keypair_path.expand_tilde());
+        let crypto = match Provider::from_keypair_path(keypair_path.clone()).await {
+            Err(Error::PassphraseRequired) => {
+                let passphrase = get_keypair_passphrase(false)?;
+                Provider::from_encrypted_keypair_path(keypair_path, &passphrase).await?
+            }
+            result => result?,
+        };
+        Ok(Arweave { crypto, ..arweave })
+    } else {
+        Ok(arweave)
+    }
+}
+
 // ====================
 // Arguments
 // ====================
@@ -666,6 +1190,18 @@ fn ar_keypair_path_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help("Specify path of keypair file to use for funding transactions.")
 }
 
+fn ar_signer_command_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("ar_signer_command")
+        .long("ar-signer-command")
+        .value_name("AR_SIGNER_COMMAND")
+        .takes_value(true)
+        .env("AR_SIGNER_COMMAND")
+        .help(
+            "Specify a command to sign with instead of a keypair file. Run with `owner` appended \
+            to print the owner and with `sign` appended to sign the Base64Url message from stdin.",
+        )
+}
+
 fn buffer_arg<'a, 'b>(default: &'a str) -> Arg<'a, 'b> {
     Arg::with_name("buffer")
         .long("buffer")
@@ -676,6 +1212,24 @@ fn buffer_arg<'a, 'b>(default: &'a str) -> Arg<'a, 'b> {
         .help("Specify the maximum number of concurrent network requests.")
 }
 
+fn bundle_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("bundle")
+        .value_name("BUNDLE")
+        .takes_value(true)
+        .required(true)
+        .validator(is_valid_bundle)
+        .help("Specify the bundle transaction id or the path of a bundle file.")
+}
+
+fn bundler_url_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("bundler_url")
+        .long("bundler-url")
+        .value_name("BUNDLER_URL")
+        .validator(is_parsable::<Url>)
+        .conflicts_with_all(&["with_sol", "no_bundle"])
+        .help("Posts files as individual data items to the bundler at this url instead of uploading bundles.")
+}
+
 fn bundle_size_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("bundle_size")
         .long("bundle-size")
@@ -686,6 +1240,45 @@ fn bundle_size_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help("Specify the bundle size in megabytes.")
 }
 
+fn data_item_target_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("data_item_target")
+        .long("data-item-target")
+        .value_name("ADDRESS")
+        .validator(is_valid_address)
+        .conflicts_with("no_bundle")
+        .help("Specify a wallet address as the target of bundled data items.")
+}
+
+fn dedupe_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("dedupe")
+        .long("dedupe")
+        .required(false)
+        .takes_value(false)
+        .help(
+            "Tags files with a File-Hash of their content and skips files already uploaded from \
+            the same wallet, reusing their ids. Not supported with --with-sol and --no-bundle.",
+        )
+}
+
+fn eth_key_path_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("eth_key_path")
+        .long("eth-key-path")
+        .value_name("ETH_KEY_PATH")
+        .validator(is_valid_file_path)
+        .env("ETH_KEY_PATH")
+        .conflicts_with_all(&["sign_with_sol", "no_bundle"])
+        .help("Specify path of file with hex encoded Ethereum private key to sign bundled data items with.")
+}
+
+fn file_path_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("file_path")
+        .value_name("FILE_PATH")
+        .takes_value(true)
+        .required(true)
+        .validator(is_valid_file_path)
+        .help("Specify the path of the file.")
+}
+
 fn file_paths_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("file_paths")
         .value_name("FILE_PATHS")
@@ -760,6 +1353,23 @@ fn no_bundle_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help("Individual transaction for each file without bundling.")
 }
 
+fn out_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("out")
+        .long("out")
+        .value_name("OUT")
+        .takes_value(true)
+        .required(true)
+}
+
+fn random_anchor_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("random_anchor")
+        .long("random-anchor")
+        .required(false)
+        .takes_value(false)
+        .conflicts_with("no_bundle")
+        .help("Gives each bundled data item a random anchor, so identical files get different ids.")
+}
+
 fn reward_multiplier_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("reward_multiplier")
         .long("reward-multiplier")
@@ -770,6 +1380,16 @@ fn reward_multiplier_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help("Specify a factor between 0.0 and 10.0 to increase the reward by.")
 }
 
+fn sign_with_sol_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("sign_with_sol")
+        .long("sign-with-sol")
+        .required(false)
+        .takes_value(false)
+        .requires("with_sol")
+        .conflicts_with("no_bundle")
+        .help("Signs bundled data items with the SOL keypair (Ed25519) instead of the AR keypair.")
+}
+
 fn sol_keypair_path_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("sol_keypair_path")
         .long("sol-keypair-path")
@@ -813,6 +1433,15 @@ fn tags_arg<'a, 'b>() -> Arg<'a, 'b> {
         )
 }
 
+fn tx_path_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("tx_path")
+        .value_name("TX_PATH")
+        .takes_value(true)
+        .required(true)
+        .validator(is_valid_file_path)
+        .help("Specify the path of the transaction json file.")
+}
+
 fn update_animation_url_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("update_animation_url")
         .long("update-animation-url")
@@ -876,6 +1505,26 @@ where
     }
 }
 
+fn is_valid_header(header: String) -> Result<(), String> {
+    match header.split_once(':') {
+        Some((name, value)) => ClientOptions::default()
+            .header(name.trim(), value.trim())
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        None => Err("Header must be formatted as NAME:VALUE.".to_string()),
+    }
+}
+
+fn is_positive<T>(value: String) -> Result<(), String>
+where
+    T: FromStr + PartialOrd + Default,
+{
+    match value.parse::<T>() {
+        Ok(n) if n > T::default() => Ok(()),
+        _ => Err("Must be a number greater than 0.".to_string()),
+    }
+}
+
 fn is_valid_reward_multiplier(reward_mult: String) -> Result<(), String> {
     match reward_mult.parse::<f32>() {
         Ok(n) => {
@@ -904,6 +1553,28 @@ fn is_valid_bundle_size(bundle_size: String) -> Result<(), String> {
     }
 }
 
+fn is_valid_address(address: String) -> Result<(), String> {
+    match Base64::from_str(&address) {
+        Ok(address) if address.0.len() == 32 => Ok(()),
+        _ => Err("Not a valid wallet address.".to_string()),
+    }
+}
+
+fn is_valid_ar_amount(ar_amount: String) -> Result<(), String> {
+    ar_to_winstons(&ar_amount)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn is_valid_bundle(bundle: String) -> Result<(), String> {
+    if PathBuf::from(&bundle).is_file() {
+        Ok(())
+    } else {
+        is_parsable::<Base64>(bundle)
+            .map_err(|_| "Not a valid file path or transaction id.".to_string())
+    }
+}
+
 fn is_valid_dir(dir_str: String) -> Result<(), String> {
     match dir_str.parse::<PathBuf>() {
         Ok(p) => {
@@ -1063,10 +1734,11 @@ impl CleanPaths for String {
 
 #[cfg(test)]
 mod tests {
-    use super::get_app;
+    use super::{get_app, get_client_options, get_transactions_query};
     use crate::CleanPaths;
     use arloader::error::Error;
     use clap::{value_t, ErrorKind};
+    use std::time::Duration;
 
     #[test]
     fn estimate() {
@@ -1303,6 +1975,96 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn base_urls() {
+        let m = get_app().get_matches_from(vec![
+            "arloader",
+            "--base-url",
+            "https://arweave.net/",
+            "--base-url",
+            "https://gateway.example/",
+            "pending",
+        ]);
+        let values: Vec<&str> = m.values_of("base_url").unwrap().collect();
+        assert_eq!(
+            values,
+            vec!["https://arweave.net/", "https://gateway.example/"]
+        );
+
+        let m = get_app().get_matches_from(vec![
+            "arloader",
+            "--base-url",
+            "https://arweave.net/,https://gateway.example/",
+            "pending",
+        ]);
+        assert_eq!(m.values_of("base_url").unwrap().count(), 2);
+    }
+
+    #[test]
+    fn client_options() -> Result<(), Error> {
+        let m = get_app().get_matches_from(vec![
+            "arloader",
+            "--timeout",
+            "0",
+            "--header",
+            "Authorization: Bearer a:b",
+            "--header",
+            "X-Team: uploads",
+            "pending",
+        ]);
+        let options = get_client_options(&m)?;
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.timeout, None);
+        assert_eq!(options.headers["authorization"], "Bearer a:b");
+        assert_eq!(options.headers["x-team"], "uploads");
+
+        let m = get_app().get_matches_from_safe(vec!["arloader", "--header", "Bearer", "pending"]);
+        assert!(m.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query() {
+        let m = get_app().get_matches_from(vec![
+            "arloader",
+            "query",
+            "--owner",
+            "7eV1qae4qVNqsNChg3Scdi-DpOLJPCogct4ixoq1WNg",
+            "--tag",
+            "Content-Type:image/png",
+            "--tag",
+            "Content-Type:image/jpeg",
+            "--min-block",
+            "930000",
+        ]);
+        let query = get_transactions_query(m.subcommand_matches("query").unwrap());
+        assert_eq!(query.owners.len(), 1);
+        assert_eq!(query.tags[0].values, vec!["image/png", "image/jpeg"]);
+        assert_eq!(query.block.unwrap().min, Some(930000));
+
+        let m = get_app().get_matches_from_safe(vec!["arloader", "query", "--limit", "10"]);
+        assert_eq!(m.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn rate_limits() {
+        let m = get_app().get_matches_from(vec![
+            "arloader",
+            "--max-rps",
+            "2.5",
+            "--max-bandwidth",
+            "1000000",
+            "pending",
+        ]);
+        assert_eq!(value_t!(m.value_of("max_rps"), f64).unwrap(), 2.5);
+        assert_eq!(value_t!(m.value_of("max_bandwidth"), u64).unwrap(), 1000000);
+
+        for (flag, value) in [("--max-rps", "0"), ("--max-bandwidth", "1.5")] {
+            let m = get_app().get_matches_from_safe(vec!["arloader", flag, value, "pending"]);
+            assert_eq!(m.unwrap_err().kind, ErrorKind::ValueValidation);
+        }
+    }
+
     #[test]
     fn update_status() {
         let m = get_app().get_matches_from(vec!["arloader", "update-status", "tests/"]);
@@ -2,6 +2,7 @@
 
 use crate::{crypto::Provider, error::Error};
 use borsh::BorshDeserialize;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 /// Single struct used for original data chunks (Leaves) and branch nodes (hashes of pairs of child nodes).
 #[derive(Debug, PartialEq, Clone)]
@@ -103,24 +104,106 @@ pub fn generate_leaves(data: Vec<u8>, crypto: &Provider) -> Result<Vec<Node>, Er
     let mut leaves = Vec::<Node>::new();
     let mut min_byte_range = 0;
     for chunk in data_chunks.into_iter() {
-        let data_hash = crypto.hash_sha256(chunk)?;
-        let max_byte_range = min_byte_range + &chunk.len();
-        let offset = max_byte_range.to_note_vec();
-        let id = crypto.hash_all_sha256(vec![&data_hash, &offset])?;
-
-        leaves.push(Node {
-            id,
-            data_hash: Some(data_hash),
-            min_byte_range,
-            max_byte_range,
-            left_child: None,
-            right_child: None,
-        });
+        leaves.push(generate_leaf(chunk, min_byte_range, crypto)?);
         min_byte_range = min_byte_range + &chunk.len();
     }
     Ok(leaves)
 }
 
+/// Generates a single leaf node from a chunk of data starting at `min_byte_range`.
+pub fn generate_leaf(
+    chunk: &[u8],
+    min_byte_range: usize,
+    crypto: &Provider,
+) -> Result<Node, Error> {
+    let data_hash = crypto.hash_sha256(chunk)?;
+    let max_byte_range = min_byte_range + chunk.len();
+    let offset = max_byte_range.to_note_vec();
+    let id = crypto.hash_all_sha256(vec![&data_hash, &offset])?;
+
+    Ok(Node {
+        id,
+        data_hash: Some(data_hash),
+        min_byte_range,
+        max_byte_range,
+        left_child: None,
+        right_child: None,
+    })
+}
+
+/// Streaming version of [`generate_leaves`] that reads data from `reader` one chunk at a time
+/// instead of requiring all of it to be in memory. Only the last two chunks are held at any
+/// point, since they may need to be rebalanced if the last one is smaller than [`MIN_CHUNK_SIZE`].
+/// Returns the leaves along with the total number of bytes read.
+pub async fn generate_leaves_from_reader<R>(
+    mut reader: R,
+    crypto: &Provider,
+) -> Result<(Vec<Node>, u64), Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut leaves = Vec::<Node>::new();
+    let mut min_byte_range = 0;
+    let mut pending = Vec::<Vec<u8>>::with_capacity(2);
+
+    loop {
+        let chunk = read_chunk(&mut reader).await?;
+        let chunk_len = chunk.len();
+        if chunk_len == 0 {
+            break;
+        }
+        if pending.len() == 2 {
+            let chunk = pending.remove(0);
+            leaves.push(generate_leaf(&chunk, min_byte_range, crypto)?);
+            min_byte_range += chunk.len();
+        }
+        pending.push(chunk);
+        if chunk_len < MAX_CHUNK_SIZE {
+            break;
+        }
+    }
+
+    if pending.len() == 2 && pending[1].len() < MIN_CHUNK_SIZE {
+        let last_two = pending.concat();
+        let chunk_size = last_two.len() / 2 + (last_two.len() % 2 != 0) as usize;
+        pending = last_two.chunks(chunk_size).map(|c| c.to_vec()).collect();
+    }
+
+    let last_is_full = pending
+        .last()
+        .map(|c| c.len() == MAX_CHUNK_SIZE)
+        .unwrap_or(true);
+
+    for chunk in pending.iter() {
+        leaves.push(generate_leaf(chunk, min_byte_range, crypto)?);
+        min_byte_range += chunk.len();
+    }
+
+    if last_is_full {
+        leaves.push(generate_leaf(&[], min_byte_range, crypto)?);
+    }
+
+    Ok((leaves, min_byte_range as u64))
+}
+
+/// Reads up to [`MAX_CHUNK_SIZE`] bytes from `reader`, only returning fewer at the end of the data.
+async fn read_chunk<R>(reader: &mut R) -> Result<Vec<u8>, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut chunk = vec![0; MAX_CHUNK_SIZE];
+    let mut filled = 0;
+    while filled < MAX_CHUNK_SIZE {
+        let read = reader.read(&mut chunk[filled..]).await?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    chunk.truncate(filled);
+    Ok(chunk)
+}
+
 /// Hashes together a single branch node from a pair of child nodes.
 pub fn hash_branch(left: Node, right: Node, crypto: &Provider) -> Result<Node, Error> {
     let max_byte_range = left.max_byte_range.to_note_vec();
@@ -213,9 +296,15 @@ pub fn validate_chunk(
     match chunk {
         Node {
             data_hash: Some(data_hash),
+            min_byte_range,
             max_byte_range,
             ..
         } => {
+            // Proofs from the network can't be trusted to be well formed.
+            if proof.proof.len() < HASH_SIZE + NOTE_SIZE {
+                return Err(Error::InvalidProof);
+            }
+
             // Split proof into branches and leaf. Leaf is at the end and branches are ordered
             // from root to leaf.
             let (branches, leaf) = proof
@@ -223,10 +312,13 @@ pub fn validate_chunk(
                 .split_at(proof.proof.len() - HASH_SIZE - NOTE_SIZE);
 
             // Deserialize proof.
-            let branch_proofs: Vec<BranchProof> = branches
-                .chunks(HASH_SIZE * 2 + NOTE_SIZE)
-                .map(|b| BranchProof::try_from_proof_slice(b).unwrap())
-                .collect();
+            let branch_chunks = branches.chunks_exact(HASH_SIZE * 2 + NOTE_SIZE);
+            if !branch_chunks.remainder().is_empty() {
+                return Err(Error::InvalidProof);
+            }
+            let branch_proofs = branch_chunks
+                .map(BranchProof::try_from_proof_slice)
+                .collect::<Result<Vec<BranchProof>, Error>>()?;
             let leaf_proof = LeafProof::try_from_proof_slice(leaf)?;
 
             // Validate branches.
@@ -243,9 +335,10 @@ pub fn validate_chunk(
                     return Err(Error::InvalidProof.into());
                 }
 
-                // If the offset from the proof is greater than the offset in the data chunk,
-                // then the next id to validate against is from the left.
-                root_id = match max_byte_range > branch_proof.offset() {
+                // If the data chunk starts at or after the offset from the proof, then the next
+                // id to validate against is from the right. Comparing the start rather than the
+                // end of the chunk also routes a trailing zero length chunk correctly.
+                root_id = match min_byte_range >= branch_proof.offset() {
                     true => branch_proof.right_id,
                     false => branch_proof.left_id,
                 }
@@ -253,7 +346,7 @@ pub fn validate_chunk(
 
             // Validate leaf: both id and data_hash are correct.
             let id = crypto.hash_all_sha256(vec![&data_hash, &max_byte_range.to_note_vec()])?;
-            if !(id == root_id) & !(data_hash == leaf_proof.data_hash) {
+            if !(id == root_id) | !(data_hash == leaf_proof.data_hash) {
                 return Err(Error::InvalidProof.into());
             }
         }
@@ -399,6 +492,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_validate_chunks_invalid() -> Result<(), Error> {
+        let crypto = Provider::default();
+        let data = fs::read("tests/fixtures/1mb.bin").await?;
+        let leaves: Vec<Node> = generate_leaves(data, &crypto)?;
+        let root = generate_data_root(leaves.clone(), &crypto)?;
+        let root_id = root.id.clone();
+        let proofs = resolve_proofs(root, None)?;
+
+        // Chunk data that doesn't match the proof.
+        let tampered = generate_leaf(&[1; MAX_CHUNK_SIZE], 0, &crypto)?;
+        assert!(validate_chunk(root_id, tampered, proofs[0].clone(), &crypto).is_err());
+
+        // Chunk validated against the proof of a different chunk.
+        assert!(validate_chunk(root_id, leaves[1].clone(), proofs[0].clone(), &crypto).is_err());
+
+        // Truncated proof.
+        let mut truncated = proofs[0].clone();
+        truncated.proof.truncate(40);
+        assert!(validate_chunk(root_id, leaves[0].clone(), truncated, &crypto).is_err());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_valid_root() -> Result<(), Error> {
         let crypto = Provider::default();
@@ -454,4 +570,19 @@ mod tests {
         assert_eq!(131072, leaves[1].max_byte_range - leaves[1].min_byte_range);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_generate_leaves_from_reader() -> Result<(), Error> {
+        let crypto = Provider::default();
+        for data in [
+            fs::read("tests/fixtures/1mb.bin").await?,
+            fs::read("tests/fixtures/rebar3").await?,
+            vec![0; 256 * 1024 + 1],
+        ] {
+            let (leaves, data_size) = generate_leaves_from_reader(&data[..], &crypto).await?;
+            assert_eq!(data_size, data.len() as u64);
+            assert_eq!(leaves, generate_leaves(data, &crypto)?);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,112 @@
+//! Client-side rate limiting of requests to gateways, by requests and bytes per second.
+
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Token bucket refilled at `rate` tokens per second, holding up to `capacity` tokens.
+///
+/// Requests for more tokens than are available take them anyway, leaving the bucket in debt,
+/// and wait until the debt would have been refilled. Later requests wait for the debt too, so
+/// requests larger than `capacity` still go through, just no faster than `rate`.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        // Allow bursts of up to a second's worth of tokens.
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Returns how long to wait before using `amount` tokens, taking them from the bucket.
+    fn take(&self, amount: f64) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let (tokens, last) = *state;
+        let now = Instant::now();
+        let refilled = now.duration_since(last).as_secs_f64() * self.rate;
+        let tokens = (tokens + refilled).min(self.capacity) - amount;
+        *state = (tokens, now);
+        if tokens < 0.0 {
+            Duration::from_secs_f64(-tokens / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Limits requests made by [`crate::Arweave`] to `max_rps` requests per second and
+/// `max_bandwidth` bytes of request bodies per second, shared by all of its uploads.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    requests: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Creates a [`RateLimiter`], with `None` leaving requests or bandwidth unlimited.
+    pub fn new(max_rps: Option<f64>, max_bandwidth: Option<u64>) -> Self {
+        Self {
+            requests: max_rps.filter(|r| *r > 0.0).map(TokenBucket::new),
+            bytes: max_bandwidth
+                .filter(|b| *b > 0)
+                .map(|b| TokenBucket::new(b as f64)),
+        }
+    }
+
+    /// Waits until a request with a body of `bytes` can be sent without going over either limit.
+    pub async fn acquire(&self, bytes: usize) {
+        let requests_wait = self
+            .requests
+            .as_ref()
+            .map_or(Duration::ZERO, |bucket| bucket.take(1.0));
+        let bytes_wait = self
+            .bytes
+            .as_ref()
+            .map_or(Duration::ZERO, |bucket| bucket.take(bytes as f64));
+        let wait = requests_wait.max(bytes_wait);
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use tokio::time::{self, Duration, Instant};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter() {
+        let start = Instant::now();
+        RateLimiter::default().acquire(1_000_000).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // A burst of 20 requests, the first 10 of which go through straight away.
+        let rate_limiter = RateLimiter::new(Some(10.0), None);
+        let start = Instant::now();
+        futures::future::join_all((0..20).map(|_| rate_limiter.acquire(0))).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        // The bucket refills while idle.
+        time::advance(Duration::from_secs(1)).await;
+        let start = Instant::now();
+        futures::future::join_all((0..10).map(|_| rate_limiter.acquire(0))).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Requests larger than a second's worth of bandwidth still go through.
+        let rate_limiter = RateLimiter::new(None, Some(10_000));
+        let start = Instant::now();
+        rate_limiter.acquire(5_000).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        rate_limiter.acquire(10_000).await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,226 @@
+//! Retrying failed requests with exponential backoff, for errors that may succeed on a retry.
+
+use crate::{error::Error, CHUNKS_RETRIES, CHUNKS_RETRY_SLEEP};
+use log::debug;
+use rand::Rng;
+use reqwest::StatusCode;
+use std::future::Future;
+use tokio::time::{sleep, Duration, Instant};
+
+/// How many times and how long to wait between retrying requests that fail with an error for
+/// which [`is_retryable`] is true. Other errors are returned without retrying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u16,
+    /// Delay before the first retry.
+    pub initial_delay: Duration,
+    /// Upper bound on the delay between retries.
+    pub max_delay: Duration,
+    /// Factor the delay grows by after each retry.
+    pub multiplier: f64,
+    /// Fraction of each delay, from 0 to 1, that is randomly taken off, so that concurrent
+    /// requests failing together don't retry together.
+    pub jitter: f64,
+    /// No further retries are made once this much time has passed since the first attempt.
+    pub max_elapsed: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: CHUNKS_RETRIES,
+            initial_delay: Duration::from_secs(CHUNKS_RETRY_SLEEP),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            max_elapsed: Some(Duration::from_secs(300)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay before retry number `retry`, counting from 0.
+    pub fn delay(&self, retry: u16) -> Duration {
+        let backoff = self.initial_delay.as_secs_f64() * self.multiplier.powi(retry as i32);
+        let delay = backoff.min(self.max_delay.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0) * rand::thread_rng().gen::<f64>();
+        Duration::from_secs_f64(delay * (1.0 - jitter))
+    }
+
+    /// Calls `f` until it succeeds, it fails with an error that isn't retryable, or the policy
+    /// runs out of retries, returning the last result.
+    pub async fn retry<T, F, Fut>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        self.retry_if(f, is_retryable).await
+    }
+
+    /// Same as [`RetryPolicy::retry`], but only retries errors for which `should_retry` is true.
+    pub async fn retry_if<T, F, Fut, P>(&self, mut f: F, should_retry: P) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+        P: Fn(&Error) -> bool,
+    {
+        let start = Instant::now();
+        let mut retries = 0;
+        loop {
+            match f().await {
+                Err(e) if retries < self.max_retries && should_retry(&e) => {
+                    let delay = self.delay(retries);
+                    if let Some(max_elapsed) = self.max_elapsed {
+                        if start.elapsed() + delay > max_elapsed {
+                            return Err(e);
+                        }
+                    }
+                    debug!("retry {} in {:?} after: {}", retries + 1, delay, e);
+                    sleep(delay).await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Returns true for response statuses that can be expected to change on a retry: timeouts,
+/// rate limiting and server errors other than those for unsupported requests.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    match status {
+        StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::NOT_IMPLEMENTED | StatusCode::HTTP_VERSION_NOT_SUPPORTED => false,
+        status => status.is_server_error(),
+    }
+}
+
+/// Returns true for errors from failed connections, timeouts, interrupted responses and
+/// responses with a retryable status.
+pub fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(e) | Error::ArweavePostError(e) | Error::OracleGetPriceError(e) => {
+            match e.status() {
+                Some(status) => is_retryable_status(status),
+                None => e.is_connect() || e.is_timeout() || e.is_request() || e.is_body(),
+            }
+        }
+        Error::ArweaveNetworkError(status) | Error::BundlerPostError(status) => {
+            is_retryable_status(*status)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true for errors from requests that weren't received, failed connections and 429
+/// responses, which are safe to retry even for requests that aren't idempotent, like payments.
+pub fn is_unsent(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(e) => match e.status() {
+            Some(status) => status == StatusCode::TOO_MANY_REQUESTS,
+            None => e.is_connect(),
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_retryable_status, is_unsent, RetryPolicy};
+    use crate::error::Error;
+    use matches::assert_matches;
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::Duration;
+
+    #[tokio::test]
+    async fn test_retry_policy() -> Result<(), Error> {
+        let policy = RetryPolicy {
+            max_retries: 4,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            ..Default::default()
+        };
+        for retry in 0..6 {
+            let delay = policy.delay(retry);
+            let backoff = Duration::from_millis(1 << retry.min(2));
+            assert!(delay <= backoff);
+            assert!(delay.as_secs_f64() >= backoff.as_secs_f64() / 2.0 - 1e-9);
+        }
+
+        // Retryable errors are retried until the request succeeds.
+        let attempts = AtomicUsize::new(0);
+        let result = policy
+            .retry(|| async {
+                match attempts.fetch_add(1, Ordering::Relaxed) {
+                    0 => Err(Error::ArweaveNetworkError(StatusCode::TOO_MANY_REQUESTS)),
+                    1 => Err(Error::ArweaveNetworkError(StatusCode::SERVICE_UNAVAILABLE)),
+                    n => Ok(n),
+                }
+            })
+            .await?;
+        assert_eq!(result, 2);
+
+        // Errors that aren't retryable are returned straight away.
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), Error> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(Error::ArweaveNetworkError(StatusCode::BAD_REQUEST))
+            })
+            .await;
+        assert_matches!(
+            result,
+            Err(Error::ArweaveNetworkError(StatusCode::BAD_REQUEST))
+        );
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+
+        // The last error is returned once retries run out.
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), Error> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(Error::ArweaveNetworkError(StatusCode::BAD_GATEWAY))
+            })
+            .await;
+        assert_matches!(
+            result,
+            Err(Error::ArweaveNetworkError(StatusCode::BAD_GATEWAY))
+        );
+        assert_eq!(attempts.load(Ordering::Relaxed), 5);
+
+        // As it is once retrying would take longer than the maximum elapsed time.
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), Error> = RetryPolicy {
+            max_elapsed: Some(Duration::from_millis(0)),
+            ..policy
+        }
+        .retry(|| async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err(Error::ArweaveNetworkError(StatusCode::BAD_GATEWAY))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+
+        // Only errors for requests that weren't received are retried with `is_unsent`.
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), Error> = policy
+            .retry_if(
+                || async {
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    Err(Error::ArweaveNetworkError(StatusCode::BAD_GATEWAY))
+                },
+                is_unsent,
+            )
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::NOT_IMPLEMENTED));
+        assert!(is_retryable_status(StatusCode::GATEWAY_TIMEOUT));
+        Ok(())
+    }
+}
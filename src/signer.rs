@@ -0,0 +1,414 @@
+//! Pluggable signers for transactions and [`crate::bundle::DataItem`]s.
+//!
+//! [`crate::crypto::Provider`] signs with a keypair loaded from a JWK file. [`ExternalSigner`]
+//! signs by running an external program, so that the keypair never has to be on the host doing
+//! the uploading. Solana [`Keypair`]s sign [`crate::bundle::DataItem`]s with Ed25519 and
+//! [`EthereumSigner`] signs them with secp256k1 keys, as used by Ethereum wallets.
+
+use crate::{crypto::Provider, error::Error, transaction::Base64};
+use libsecp256k1::{Message, PublicKey, RecoveryId, SecretKey};
+use ring::{
+    digest::{Context, SHA256},
+    signature,
+};
+use sha3::{Digest, Keccak256};
+use solana_sdk::signer::{keypair::Keypair, Signer as SolanaSigner};
+use std::{
+    fmt, fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    str::FromStr,
+};
+use tokio::{
+    runtime::{Handle, RuntimeFlavor},
+    task,
+};
+
+/// ANS-104 signature type for Arweave RSA-PSS signatures.
+pub const SIGNATURE_TYPE_ARWEAVE: u16 = 1;
+
+/// ANS-104 signature type for Ed25519 signatures, as used by Solana keypairs.
+pub const SIGNATURE_TYPE_ED25519: u16 = 2;
+
+/// ANS-104 signature type for Ethereum secp256k1 signatures.
+pub const SIGNATURE_TYPE_ETHEREUM: u16 = 3;
+
+/// Returns the lengths in bytes of the signature and owner for an ANS-104 signature type.
+pub fn signature_lengths(signature_type: u16) -> Result<(usize, usize), Error> {
+    match signature_type {
+        SIGNATURE_TYPE_ARWEAVE => Ok((512, 512)),
+        SIGNATURE_TYPE_ED25519 => Ok((64, 32)),
+        SIGNATURE_TYPE_ETHEREUM => Ok((65, 65)),
+        _ => Err(Error::UnsupportedSignatureType(signature_type)),
+    }
+}
+
+/// Verifies that `message` was signed by `owner` with a signature of `signature_type`.
+pub fn verify_signature(
+    signature_type: u16,
+    owner: &[u8],
+    signature: &[u8],
+    message: &[u8],
+) -> Result<(), Error> {
+    match signature_type {
+        SIGNATURE_TYPE_ARWEAVE => {
+            let public_key = signature::RsaPublicKeyComponents {
+                n: owner,
+                e: &[1, 0, 1][..],
+            };
+            public_key.verify(&signature::RSA_PSS_2048_8192_SHA256, message, signature)?;
+        }
+        SIGNATURE_TYPE_ED25519 => {
+            signature::UnparsedPublicKey::new(&signature::ED25519, owner)
+                .verify(message, signature)?;
+        }
+        SIGNATURE_TYPE_ETHEREUM => {
+            let public_key = PublicKey::parse_slice(owner, None)?;
+            let signature = libsecp256k1::Signature::parse_standard_slice(
+                signature.get(..64).ok_or(Error::InvalidSignature)?,
+            )?;
+            if !libsecp256k1::verify(&eth_message_hash(message), &signature, &public_key) {
+                return Err(Error::InvalidSignature);
+            }
+        }
+        _ => return Err(Error::UnsupportedSignatureType(signature_type)),
+    }
+    Ok(())
+}
+
+/// Signs deep hashes of transactions and [`crate::bundle::DataItem`]s.
+pub trait Signer: Send + Sync {
+    /// ANS-104 signature type of the signatures created.
+    fn signature_type(&self) -> u16;
+
+    /// Public key, included as the `owner` of transactions and data items.
+    fn owner(&self) -> Result<Base64, Error>;
+
+    /// Signs a message, which is the deep hash of a transaction or data item.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Address of the wallet, the SHA256 hash of the owner for Arweave keys.
+    fn wallet_address(&self) -> Result<Base64, Error> {
+        let mut context = Context::new(&SHA256);
+        context.update(&self.owner()?.0);
+        Ok(Base64(context.finish().as_ref().to_vec()))
+    }
+}
+
+impl Signer for Provider {
+    fn signature_type(&self) -> u16 {
+        SIGNATURE_TYPE_ARWEAVE
+    }
+
+    fn owner(&self) -> Result<Base64, Error> {
+        self.keypair_modulus()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        Provider::sign(self, message)
+    }
+
+    fn wallet_address(&self) -> Result<Base64, Error> {
+        Provider::wallet_address(self)
+    }
+}
+
+impl Signer for Keypair {
+    fn signature_type(&self) -> u16 {
+        SIGNATURE_TYPE_ED25519
+    }
+
+    fn owner(&self) -> Result<Base64, Error> {
+        Ok(Base64(self.pubkey().to_bytes().to_vec()))
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(self.sign_message(message).as_ref().to_vec())
+    }
+}
+
+/// Hashes `message` as an Ethereum signed message, per EIP-191, so that signatures match those of
+/// `personal_sign` in Ethereum wallets.
+fn eth_message_hash(message: &[u8]) -> Message {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes());
+    hasher.update(message);
+    Message::parse(&hasher.finalize().into())
+}
+
+/// Signs with a secp256k1 private key, as used by Ethereum wallets.
+pub struct EthereumSigner {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+}
+
+impl EthereumSigner {
+    /// Creates an [`EthereumSigner`] from a hex encoded private key, with or without a `0x`
+    /// prefix.
+    pub fn from_hex(private_key: &str) -> Result<Self, Error> {
+        let private_key = private_key.trim();
+        let bytes = hex::decode(private_key.strip_prefix("0x").unwrap_or(private_key))?;
+        let secret_key = SecretKey::parse_slice(&bytes)?;
+        Ok(Self {
+            public_key: PublicKey::from_secret_key(&secret_key),
+            secret_key,
+        })
+    }
+
+    /// Reads a hex encoded private key from a file.
+    pub fn from_key_path(key_path: PathBuf) -> Result<Self, Error> {
+        Self::from_hex(&fs::read_to_string(key_path)?)
+    }
+
+    /// Ethereum address, the last 20 bytes of the keccak256 hash of the public key, hex encoded
+    /// with a `0x` prefix.
+    pub fn address(&self) -> String {
+        let hash = Keccak256::digest(&self.public_key.serialize()[1..]);
+        format!("0x{}", hex::encode(&hash[12..]))
+    }
+}
+
+// Only show the address so the private key doesn't end up in logs.
+impl fmt::Debug for EthereumSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EthereumSigner")
+            .field("address", &self.address())
+            .finish()
+    }
+}
+
+impl Signer for EthereumSigner {
+    fn signature_type(&self) -> u16 {
+        SIGNATURE_TYPE_ETHEREUM
+    }
+
+    /// Uncompressed public key.
+    fn owner(&self) -> Result<Base64, Error> {
+        Ok(Base64(self.public_key.serialize().to_vec()))
+    }
+
+    /// Signature as `r || s || v`, with `v` either 27 or 28.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        let (signature, recovery_id): (_, RecoveryId) =
+            libsecp256k1::sign(&eth_message_hash(message), &self.secret_key);
+        let mut signature = signature.serialize().to_vec();
+        signature.push(recovery_id.serialize() + 27);
+        Ok(signature)
+    }
+}
+
+/// Signs by running an external program, such as a client for a remote signing service or a
+/// hardware key.
+///
+/// The program is run with `owner` appended to its arguments to get the owner, which it prints
+/// to stdout as a Base64Url string. To sign, it is run with `sign` appended to its arguments,
+/// gets the Base64Url encoded message on stdin and prints the Base64Url encoded signature to
+/// stdout. The owner is only requested once, when the signer is created.
+#[derive(Debug)]
+pub struct ExternalSigner {
+    pub program: String,
+    pub args: Vec<String>,
+    pub signature_type: u16,
+    owner: Base64,
+}
+
+impl ExternalSigner {
+    /// Creates an [`ExternalSigner`] from a command line, the program followed by any arguments,
+    /// separated by whitespace.
+    pub fn from_command(command: &str, signature_type: u16) -> Result<Self, Error> {
+        let mut parts = command.split_whitespace().map(String::from);
+        let program = parts
+            .next()
+            .ok_or(Error::ExternalSigner("empty signer command".to_string()))?;
+        Self::new(program, parts.collect(), signature_type)
+    }
+
+    pub fn new(program: String, args: Vec<String>, signature_type: u16) -> Result<Self, Error> {
+        let mut signer = Self {
+            program,
+            args,
+            signature_type,
+            owner: Base64::default(),
+        };
+        signer.owner = Base64::from_str(&signer.run("owner", None)?)?;
+        Ok(signer)
+    }
+
+    /// Runs the program with `action` appended to its arguments, writing `input` to stdin if
+    /// provided and returning stdout, trimmed.
+    ///
+    /// Signing can take a while on a hardware key or remote service, so when called from a worker
+    /// thread of a multi-threaded runtime, other tasks are moved off the thread while it runs.
+    fn run(&self, action: &str, input: Option<&str>) -> Result<String, Error> {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                task::block_in_place(|| self.run_blocking(action, input))
+            }
+            _ => self.run_blocking(action, input),
+        }
+    }
+
+    fn run_blocking(&self, action: &str, input: Option<&str>) -> Result<String, Error> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .arg(action)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        if let Some(input) = input {
+            child
+                .stdin
+                .take()
+                .ok_or(Error::ExternalSigner("stdin not available".to_string()))?
+                .write_all(input.as_bytes())?;
+        } else {
+            drop(child.stdin.take());
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::ExternalSigner(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+}
+
+impl Signer for ExternalSigner {
+    fn signature_type(&self) -> u16 {
+        self.signature_type
+    }
+
+    fn owner(&self) -> Result<Base64, Error> {
+        Ok(self.owner.clone())
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        let signature = self.run("sign", Some(&Base64(message.to_vec()).to_string()))?;
+        Ok(Base64::from_str(&signature)?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        signature_lengths, verify_signature, EthereumSigner, ExternalSigner, Signer,
+        SIGNATURE_TYPE_ARWEAVE, SIGNATURE_TYPE_ED25519, SIGNATURE_TYPE_ETHEREUM,
+    };
+    use crate::{crypto::Provider, error::Error, transaction::Base64};
+    use matches::assert_matches;
+    use solana_sdk::signer::keypair::Keypair;
+
+    #[test]
+    fn test_provider_signer() -> Result<(), Error> {
+        let provider = Provider::default();
+        let signer: &dyn Signer = &provider;
+        assert_eq!(signer.signature_type(), SIGNATURE_TYPE_ARWEAVE);
+        assert_eq!(signer.owner()?, provider.keypair_modulus()?);
+        assert_eq!(signer.wallet_address()?, provider.wallet_address()?);
+
+        let signature = signer.sign(b"hello, world")?;
+        provider.verify(&signature, b"hello, world")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_solana_signer() -> Result<(), Error> {
+        let keypair = Keypair::new();
+        let signer: &dyn Signer = &keypair;
+        let (signature_len, owner_len) = signature_lengths(signer.signature_type())?;
+        let owner = signer.owner()?;
+        assert_eq!(signer.signature_type(), SIGNATURE_TYPE_ED25519);
+        assert_eq!(owner.0.len(), owner_len);
+
+        let signature = signer.sign(b"hello, world")?;
+        assert_eq!(signature.len(), signature_len);
+        verify_signature(
+            SIGNATURE_TYPE_ED25519,
+            &owner.0,
+            &signature,
+            b"hello, world",
+        )?;
+        assert_matches!(
+            verify_signature(
+                SIGNATURE_TYPE_ED25519,
+                &owner.0,
+                &signature,
+                b"hello, world!"
+            ),
+            Err(Error::RingUnspecified(_))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ethereum_signer() -> Result<(), Error> {
+        // Well known test key, with address from ethers.js.
+        let signer = EthereumSigner::from_hex(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )?;
+        assert_eq!(
+            signer.address(),
+            "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23"
+        );
+
+        let (signature_len, owner_len) = signature_lengths(signer.signature_type())?;
+        let owner = signer.owner()?;
+        assert_eq!(signer.signature_type(), SIGNATURE_TYPE_ETHEREUM);
+        assert_eq!(owner.0.len(), owner_len);
+
+        let signature = signer.sign(b"hello, world")?;
+        assert_eq!(signature.len(), signature_len);
+        assert!(signature[64] == 27 || signature[64] == 28);
+        verify_signature(
+            SIGNATURE_TYPE_ETHEREUM,
+            &owner.0,
+            &signature,
+            b"hello, world",
+        )?;
+        assert_matches!(
+            verify_signature(
+                SIGNATURE_TYPE_ETHEREUM,
+                &owner.0,
+                &signature,
+                b"hello, world!"
+            ),
+            Err(Error::InvalidSignature)
+        );
+        assert_matches!(
+            EthereumSigner::from_hex("not hex"),
+            Err(Error::HexDecode(_))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_signer() -> Result<(), Error> {
+        check_external_signer()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_external_signer_in_runtime() -> Result<(), Error> {
+        check_external_signer()
+    }
+
+    fn check_external_signer() -> Result<(), Error> {
+        // Stand-in signer that returns a fixed owner and echoes the message as the signature.
+        let script = r#"if [ "$1" = owner ]; then echo LCwsLCwsLA; else cat; fi"#;
+        let signer = ExternalSigner::new(
+            "sh".to_string(),
+            vec!["-c".to_string(), script.to_string(), "signer".to_string()],
+            SIGNATURE_TYPE_ARWEAVE,
+        )?;
+        assert_eq!(signer.owner()?, Base64(vec![44; 7]));
+        assert_eq!(signer.sign(&[1, 2, 3])?, vec![1, 2, 3]);
+
+        let error = ExternalSigner::from_command("sh -c exit-1", SIGNATURE_TYPE_ARWEAVE);
+        assert_matches!(error, Err(Error::ExternalSigner(_)));
+        Ok(())
+    }
+}
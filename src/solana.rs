@@ -3,6 +3,7 @@
 use crate::error::Error;
 use crate::transaction::{Base64, DeepHashItem};
 use futures::future::try_join;
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use solana_sdk::{
@@ -29,9 +30,7 @@ pub const RATE: u64 = 2500;
 pub const FLOOR: u64 = 10000;
 
 /// Returns recent blockhash neeed to create transaction.
-pub async fn get_recent_blockhash(base_url: url::Url) -> Result<Hash, Error> {
-    let client = reqwest::Client::new();
-
+pub async fn get_recent_blockhash(client: &Client, base_url: url::Url) -> Result<Hash, Error> {
     let mut config = serde_json::Map::new();
     config.insert(
         "commitment".to_string(),
@@ -48,6 +47,7 @@ pub async fn get_recent_blockhash(base_url: url::Url) -> Result<Hash, Error> {
         .json(&post_object)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
 
@@ -58,11 +58,10 @@ pub async fn get_recent_blockhash(base_url: url::Url) -> Result<Hash, Error> {
 
 /// Returns wallet balance.
 pub async fn get_sol_wallet_balance(
+    client: &Client,
     base_url: url::Url,
     keypair: &keypair::Keypair,
 ) -> Result<u64, Error> {
-    let client = reqwest::Client::new();
-
     let mut config = serde_json::Map::new();
     config.insert("commitment".to_string(), json!("confirmed".to_string()));
 
@@ -77,6 +76,7 @@ pub async fn get_sol_wallet_balance(
         .json(&post_object)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
 
@@ -85,9 +85,11 @@ pub async fn get_sol_wallet_balance(
 }
 
 /// Airdrops tokens from devnet for testing purposes.
-pub async fn request_airdrop(base_url: url::Url, keypair: &keypair::Keypair) -> Result<(), Error> {
-    let client = reqwest::Client::new();
-
+pub async fn request_airdrop(
+    client: &Client,
+    base_url: url::Url,
+    keypair: &keypair::Keypair,
+) -> Result<(), Error> {
     let mut config = serde_json::Map::new();
     config.insert("commitment".to_string(), json!("confirmed".to_string()));
 
@@ -106,13 +108,14 @@ pub async fn request_airdrop(base_url: url::Url, keypair: &keypair::Keypair) ->
 
 /// Creates Solana transaction.
 pub async fn create_sol_transaction(
+    client: &Client,
     base_url: url::Url,
     from_keypair: &keypair::Keypair,
     lamports: u64,
 ) -> Result<String, Error> {
     let (recent_blockhash, balance) = try_join(
-        get_recent_blockhash(base_url.clone()),
-        get_sol_wallet_balance(base_url, from_keypair),
+        get_recent_blockhash(client, base_url.clone()),
+        get_sol_wallet_balance(client, base_url, from_keypair),
     )
     .await?;
 
@@ -133,12 +136,11 @@ pub async fn create_sol_transaction(
 
 /// Submits Solana transaction and required transaction elements and gets back signed AR transaction.
 pub async fn get_sol_ar_signature(
+    client: &Client,
     base_url: url::Url,
     deep_hash_item: DeepHashItem,
     sol_tx: String,
 ) -> Result<SigResponse, Error> {
-    let client = reqwest::Client::new();
-
     let tx_data = TxData {
         deep_hash_item,
         sol_tx,
@@ -149,6 +151,7 @@ pub async fn get_sol_ar_signature(
         .json(&tx_data)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
 
@@ -202,7 +205,7 @@ mod tests {
     async fn test_get_recent_blockhash() -> Result<(), Error> {
         let base_url = SOLANA_DEV_URL.parse::<url::Url>().unwrap();
 
-        let result = get_recent_blockhash(base_url).await?;
+        let result = get_recent_blockhash(&Client::new(), base_url).await?;
         println!("{}", result);
         Ok(())
     }
@@ -211,9 +214,10 @@ mod tests {
     async fn test_get_sol_transaction() -> Result<(), Error> {
         let base_url = SOLANA_DEV_URL.parse::<url::Url>().unwrap();
         let keypair = keypair::read_keypair_file("tests/fixtures/solana_test.json")?;
-        request_airdrop(base_url.clone(), &keypair).await?;
+        let client = Client::new();
+        request_airdrop(&client, base_url.clone(), &keypair).await?;
 
-        let result = create_sol_transaction(base_url, &keypair, 42).await?;
+        let result = create_sol_transaction(&client, base_url, &keypair, 42).await?;
         println!("{}", result);
         Ok(())
     }
@@ -223,7 +227,7 @@ mod tests {
         let base_url = SOLANA_DEV_URL.parse::<url::Url>().unwrap();
         let keypair = Keypair::new();
 
-        let balance = get_sol_wallet_balance(base_url, &keypair).await?;
+        let balance = get_sol_wallet_balance(&Client::new(), base_url, &keypair).await?;
         println!("{}", balance);
         Ok(())
     }
@@ -19,6 +19,20 @@ pub struct RawStatus {
     pub number_of_confirmations: u64,
 }
 
+/// Receipt returned by a bundler for a posted [`crate::bundle::DataItem`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BundlerReceipt {
+    pub id: Base64,
+    pub timestamp: Option<u64>,
+    pub version: Option<String>,
+    /// Public key of the bundler.
+    pub public: Option<String>,
+    /// Bundler's signature over the receipt.
+    pub signature: Option<String>,
+    /// Block height by which the bundler commits to include the data item.
+    pub block: Option<u64>,
+}
+
 /// Indicates transaction status on the network, from Submitted to Confirmed.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq, Hash)]
 pub enum StatusCode {
@@ -66,6 +80,8 @@ pub struct Status {
     pub raw_status: Option<RawStatus>,
     #[serde(flatten)]
     pub sol_sig: Option<SigResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundler_receipt: Option<BundlerReceipt>,
 }
 
 impl Default for Status {
@@ -80,6 +96,7 @@ impl Default for Status {
             reward: 0,
             raw_status: None,
             sol_sig: None,
+            bundler_receipt: None,
         }
     }
 }
@@ -185,6 +202,11 @@ pub struct BundleStatus {
     pub raw_status: Option<RawStatus>,
     #[serde(flatten)]
     pub sol_sig: Option<SigResponse>,
+    /// Set when all the files of a bundle were found already uploaded and no bundle was posted.
+    /// `file_paths` then has the ids the files were uploaded with and `id` is the first of them,
+    /// so the status gets neither updated nor re-uploaded.
+    #[serde(default)]
+    pub deduplicated: bool,
 }
 
 impl Default for BundleStatus {
@@ -200,6 +222,7 @@ impl Default for BundleStatus {
             reward: 0,
             raw_status: None,
             sol_sig: None,
+            deduplicated: false,
         }
     }
 }
@@ -0,0 +1,101 @@
+//! Stand-in HTTP server for testing requests to gateways, bundlers and proxies.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    str::FromStr,
+    thread::JoinHandle,
+};
+use url::Url;
+
+/// Request received by [`serve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path requested, or the absolute url for requests sent through a proxy.
+    pub target: String,
+    /// Headers with lowercase names.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Returns the value of header `name`, if the request has it.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Response sent by [`serve`], a status like `200 OK` and a JSON body.
+pub type Response = (&'static str, String);
+
+/// Serves `responses` in turn, one per connection, returning the url to send requests to and a
+/// handle that joins with the requests received once all responses have been sent.
+pub fn serve(responses: Vec<Response>) -> (Url, JoinHandle<Vec<Request>>) {
+    let mut responses = responses.into_iter();
+    serve_with(responses.len(), move |_| responses.next().unwrap())
+}
+
+/// Same as [`serve`], but serves `requests` requests, responding to each with `respond`.
+pub fn serve_with<F>(requests: usize, mut respond: F) -> (Url, JoinHandle<Vec<Request>>)
+where
+    F: FnMut(&Request) -> Response + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::from_str(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+    let server = std::thread::spawn(move || {
+        (0..requests)
+            .map(|_| {
+                let (stream, _) = listener.accept().unwrap();
+                let request = read_request(&mut BufReader::new(stream.try_clone().unwrap()));
+                let (status, body) = respond(&request);
+                write!(
+                    &stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                request
+            })
+            .collect()
+    });
+    (url, server)
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> Request {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split(' ');
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" || line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = Request {
+        method,
+        target,
+        headers,
+        body: Vec::new(),
+    };
+    let content_length = request
+        .header("content-length")
+        .map_or(0, |l| l.parse().unwrap());
+    request.body = vec![0; content_length];
+    reader.read_exact(&mut request.body).unwrap();
+    request
+}
@@ -1,11 +1,22 @@
 //! Data structures for serializing and deserializing [`Transaction`]s and [`Tag`]s.
 
 use crate::{
+    crypto::{hash_sha256, transaction_signature_data},
     error::Error,
     merkle::{Node, Proof},
+    signer::{verify_signature, SIGNATURE_TYPE_ARWEAVE},
 };
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
-use std::str::FromStr;
+use std::{
+    collections::BTreeSet,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
 
 /// Transaction data structure per [Arweave transaction spec](https://docs.arweave.org/developers/server/http-api#transaction-format).
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
@@ -43,6 +54,23 @@ pub struct Chunk {
     chunk: Base64,
 }
 
+/// Size and absolute end offset of transaction data in the weave, as returned by `tx/{id}/offset`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct TransactionOffset {
+    #[serde(with = "stringify")]
+    pub size: usize,
+    #[serde(with = "stringify")]
+    pub offset: usize,
+}
+
+/// Chunk data structure as returned by `chunk/{offset}`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct RawChunk {
+    pub tx_path: Base64,
+    pub data_path: Base64,
+    pub chunk: Base64,
+}
+
 /// Serializes and deserializes numbers represented as Strings. Used for `quantity`, `data_size`
 /// and `reward` [`Transaction`] fields so that they can be represented as numbers but be serialized
 /// to Strings as required by the Arweave spec.
@@ -88,6 +116,48 @@ impl Transaction {
             proofs: Vec::new(),
         })
     }
+
+    /// Verifies the transaction's signature against the RSA public key with the `owner` modulus,
+    /// over the signature data recomputed from its fields, and checks that `id` is the SHA-256
+    /// hash of the signature.
+    pub fn verify(&self) -> Result<(), Error> {
+        if self.signature.0.is_empty() {
+            return Err(Error::UnsignedTransaction);
+        }
+        let signature_data = transaction_signature_data(self)?;
+        verify_signature(
+            SIGNATURE_TYPE_ARWEAVE,
+            &self.owner.0,
+            &self.signature.0,
+            &signature_data,
+        )
+        .map_err(|_| Error::InvalidSignature)?;
+
+        if hash_sha256(&self.signature.0)[..] != self.id.0[..] {
+            return Err(Error::TransactionIdMismatch);
+        }
+        Ok(())
+    }
+
+    /// Returns the message signed for format 1 transactions, the concatenation of `owner`,
+    /// `target`, inline `data`, `quantity`, `reward`, `last_tx` and tag names and values.
+    pub fn format_1_signature_data(&self) -> Vec<u8> {
+        let mut signature_data = [
+            &self.owner.0[..],
+            &self.target.0,
+            &self.data.0,
+            self.quantity.to_string().as_bytes(),
+            self.reward.to_string().as_bytes(),
+            &self.last_tx.0,
+        ]
+        .concat();
+        for tag in &self.tags {
+            signature_data.extend_from_slice(&tag.name.0);
+            signature_data.extend_from_slice(&tag.value.0);
+        }
+        signature_data
+    }
+
     pub fn get_chunk(&self, idx: usize) -> Result<Chunk, Error> {
         Ok(Chunk {
             data_root: self.data_root.clone(),
@@ -100,6 +170,25 @@ impl Transaction {
             ),
         })
     }
+
+    /// Same as [`Transaction::get_chunk`], but reads the chunk data from `file_path` rather than
+    /// from `data`, for transactions created without holding their data in memory.
+    pub async fn get_chunk_from_file(&self, idx: usize, file_path: &Path) -> Result<Chunk, Error> {
+        let node = &self.chunks[idx];
+        let mut chunk = vec![0; node.max_byte_range - node.min_byte_range];
+        let mut file = File::open(file_path).await?;
+        file.seek(SeekFrom::Start(node.min_byte_range as u64))
+            .await?;
+        file.read_exact(&mut chunk).await?;
+
+        Ok(Chunk {
+            data_root: self.data_root.clone(),
+            data_size: self.data_size,
+            data_path: Base64(self.proofs[idx].proof.clone()),
+            offset: self.proofs[idx].offset,
+            chunk: Base64(chunk),
+        })
+    }
 }
 
 /// Implemented on [`Transaction`] to create root [`DeepHashItem`]s used by
@@ -110,24 +199,10 @@ pub trait ToItems<'a, T> {
 }
 
 impl<'a> ToItems<'a, Transaction> for Transaction {
+    /// Only format 2 transactions are signed over a deep hash, see
+    /// [`Transaction::format_1_signature_data`] for format 1.
     fn to_deep_hash_item(&'a self) -> Result<DeepHashItem, Error> {
         match &self.format {
-            1 => {
-                let mut children: Vec<DeepHashItem> = vec![
-                    &self.owner.0[..],
-                    &self.target.0,
-                    &self.data.0,
-                    self.quantity.to_string().as_bytes(),
-                    self.reward.to_string().as_bytes(),
-                    &self.last_tx.0,
-                ]
-                .into_iter()
-                .map(DeepHashItem::from_item)
-                .collect();
-                children.push(self.tags.to_deep_hash_item()?);
-
-                Ok(DeepHashItem::from_children(children))
-            }
             2 => {
                 let mut children: Vec<DeepHashItem> = vec![
                     self.format.to_string().as_bytes(),
@@ -148,7 +223,7 @@ impl<'a> ToItems<'a, Transaction> for Transaction {
 
                 Ok(DeepHashItem::from_children(children))
             }
-            _ => unreachable!(),
+            format => Err(Error::UnsupportedTransactionFormat(*format)),
         }
     }
 }
@@ -287,21 +362,32 @@ impl DeepHashItem {
     }
 }
 
-/// Not currently in use.
+/// Progress of a chunked upload, written to the log dir as `uploader_<ID>.json` so that an upload
+/// that fails partway through can be resumed with [`crate::Arweave::resume_upload`].
+///
+/// `transaction` is stored without data and chunks, which get re-created from `file_path` on resume.
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct Uploader {
-    pub chunk_index: usize,
     pub tx_posted: bool,
     pub transaction: Transaction,
+    pub file_path: Option<PathBuf>,
+    pub number_of_chunks: usize,
+    pub posted_chunks: BTreeSet<usize>,
     pub last_request_time_end: u64,
     pub total_errors: u64,
-    pub last_response_status: u64,
     pub last_response_error: String,
 }
 
 impl Uploader {
     pub fn is_complete(&self) -> bool {
-        self.tx_posted && self.chunk_index == self.transaction.chunks.len()
+        self.tx_posted && self.posted_chunks.len() == self.number_of_chunks
+    }
+
+    /// Indices of chunks that haven't been posted yet.
+    pub fn missing_chunks(&self) -> Vec<usize> {
+        (0..self.number_of_chunks)
+            .filter(|i| !self.posted_chunks.contains(i))
+            .collect()
     }
 }
 
@@ -12,7 +12,7 @@ use futures::{future::try_join_all, StreamExt};
 use glob::glob;
 use solana_sdk::signer::keypair;
 use std::{iter, path::PathBuf, str::FromStr, time::Duration};
-use tokio::time::sleep;
+use tokio::{fs, time::sleep};
 use url::Url;
 
 async fn get_arweave() -> Result<Arweave, Error> {
@@ -95,6 +95,35 @@ async fn test_upload_file_from_path() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_download_transaction_data() -> Result<(), Error> {
+    let arweave = get_arweave().await?;
+    // Don't run if test server is not running.
+    if let Err(_) = reqwest::get(arweave.base_url.join("info")?).await {
+        println!("Test server not running.");
+        return Ok(());
+    }
+
+    airdrop(&arweave).await?;
+    let data = fs::read("tests/fixtures/1mb.bin").await?;
+    let transaction = arweave
+        .create_transaction(data.clone(), None, None, (0, 0), true)
+        .await?;
+    let signed_transaction = arweave.sign_transaction(transaction)?;
+    let (id, _) = arweave
+        .post_transaction_chunks(signed_transaction, 5)
+        .await?;
+    mine(&arweave).await?;
+
+    let mut downloaded = Vec::new();
+    let len = arweave
+        .download_transaction_data(&id, &mut downloaded)
+        .await?;
+    assert_eq!(len, data.len() as u64);
+    assert_eq!(downloaded, data);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_update_status() -> Result<(), Error> {
     let arweave = get_arweave().await?;